@@ -9,6 +9,48 @@ warning-title = Warning
 # Player ui
 seeking = Seeking...
 buffering = Buffering...
+statistics = Statistics
+    .decoder = Decoder: {$decoder}
+    .caps = Caps: {$caps}
+    .rendered = Rendered frames: {$frames}
+    .dropped = Dropped frames: {$frames}
+    .fps = FPS: {$fps}
+    .bitrate = Bitrate: {$kbits} kbit/s
+    .bitrate-unknown = Bitrate: -
+    .unknown = -
+
+# Audio level meter ui
+audio-level = Channel {$channel}: RMS {$rms} dB, peak {$peak} dB
+
+# Media info ui
+media-info = Media info
+    .copy = Copy to clipboard
+    .none = No media info available
+    .uri = URI: {$uri}
+    .title = Title: {$title}
+    .container = Container: {$container}
+    .duration = Duration: {$duration} s
+    .live = Live: {$live}
+    .seekable = Seekable: {$seekable}
+    .yes = yes
+    .no = no
+    .stream = Stream #{$index}
+    .type = Type: {$kind}
+    .video = Video
+    .audio = Audio
+    .subtitle = Subtitle
+    .other = Other
+    .codec = Codec: {$codec}
+    .language = Language: {$language}
+    .resolution = Resolution: {$width}x{$height}
+    .framerate = Framerate: {$framerate} fps
+    .sample-rate = Sample rate: {$rate} Hz
+    .channels = Channels: {$channels}
+    .bitrate = Bitrate: {$bitrate} bit/s
+    .chapters = Chapters:
+    .chapter = {$title}: {$start} s - {$stop} s
+    .chapter-open = {$title}: {$start} s
+    .tags = Tags:
 
 # Playlist ui
 playlist = Playlist
@@ -18,6 +60,19 @@ remove-file = Remove file
 repeat = Repeat
     .all = Repeat all
     .none = Repeat none
+    .one = Repeat one
+shuffle = Shuffle
+    .on = Shuffle on
+    .off = Shuffle off
+search = Search
+    .desc = Filter by file name, tags or codec, restrict the start time with from:YYYY-MM-DD and to:YYYY-MM-DD
+    .navigation = Filtered only
+    .navigation-desc = Skip entries, which do not match the filter, when switching to the next or previous entry
+selection = Selection
+    .select-all = Select all
+    .remove = Remove selected
+    .fetch-metadata = Refresh metadata
+    .copy-uris = Copy URIs
 sort-by = Sort by
     .start-time = Start time
     .file-name = File name
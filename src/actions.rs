@@ -6,6 +6,9 @@ pub enum Accelerators {
     TogglePlaylistVisibility,
     TogglePlayPause,
     NextFrame,
+    PreviousFrame,
+    NextChapter,
+    PreviousChapter,
     Previous,
     Next,
     IncreaseVolume,
@@ -13,6 +16,7 @@ pub enum Accelerators {
     IncreaseSpeed,
     DecreaseSpeed,
     ResetSpeed,
+    ReversePlayback,
     FullScreen,
     DumpPipeline,
     Preferences,
@@ -50,6 +54,9 @@ pub fn accelerators(accel: Accelerators) -> Vec<&'static str> {
         Accelerators::TogglePlaylistVisibility => vec![action_accelerator_with_os_modifier!("B")],
         Accelerators::TogglePlayPause => vec!["space", "AudioPlay", action_accelerator_with_os_modifier!("space")],
         Accelerators::NextFrame => vec![action_accelerator_with_os_modifier!("<Alt>Right")],
+        Accelerators::PreviousFrame => vec![action_accelerator_with_os_modifier!("<Alt>Left")],
+        Accelerators::NextChapter => vec![action_accelerator_with_os_modifier!("Page_Down")],
+        Accelerators::PreviousChapter => vec![action_accelerator_with_os_modifier!("Page_Up")],
         Accelerators::Previous => vec![action_accelerator_with_os_modifier!("Left"), "AudioPrev"],
         Accelerators::Next => vec![action_accelerator_with_os_modifier!("Right"), "AudioNext"],
         Accelerators::IncreaseVolume => vec![action_accelerator_with_os_modifier!("Up")],
@@ -57,6 +64,7 @@ pub fn accelerators(accel: Accelerators) -> Vec<&'static str> {
         Accelerators::IncreaseSpeed => vec![action_accelerator_with_os_modifier!("<Alt>plus")],
         Accelerators::DecreaseSpeed => vec![action_accelerator_with_os_modifier!("<Alt>minus")],
         Accelerators::ResetSpeed => vec![action_accelerator_with_os_modifier!("<Alt>0")],
+        Accelerators::ReversePlayback => vec![action_accelerator_with_os_modifier!("<Alt>r")],
         Accelerators::FullScreen => vec![action_accelerator_with_os_modifier!("F")],
         Accelerators::DumpPipeline => vec![action_accelerator_with_os_modifier!("D")],
         Accelerators::Preferences => vec![action_accelerator_with_os_modifier!("comma")],
@@ -0,0 +1,83 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Chapter {
+    pub uid: String,
+    pub title: Option<String>,
+    /// Start of the chapter in seconds.
+    pub start: f64,
+    /// End of the chapter in seconds, if known.
+    pub stop: Option<f64>,
+}
+
+/// Collects the chapters of the table of contents sorted by their start.
+///
+/// Nested chapters are flattened to their innermost chapters, so that the chapters do not overlap.
+pub fn chapters_from_toc(toc: &gst::TocRef) -> Vec<Chapter> {
+    let mut chapters = Vec::new();
+    for entry in toc.entries() {
+        collect_chapters(&entry, &mut chapters);
+    }
+    chapters.sort_by(|a, b| a.start.total_cmp(&b.start));
+    chapters
+}
+
+fn collect_chapters(entry: &gst::TocEntry, chapters: &mut Vec<Chapter>) {
+    let sub_entries = entry.sub_entries();
+    let has_sub_chapters = sub_entries
+        .iter()
+        .any(|sub_entry| sub_entry.entry_type() == gst::TocEntryType::Chapter);
+    if entry.entry_type() == gst::TocEntryType::Chapter && !has_sub_chapters {
+        if let Some((start, stop)) = entry.start_stop_times() {
+            chapters.push(Chapter {
+                uid: entry.uid().to_string(),
+                title: entry
+                    .tags()
+                    .and_then(|tags| tags.get::<gst::tags::Title>().map(|x| x.get().to_string())),
+                start: nanoseconds_to_seconds(start).unwrap_or_default(),
+                stop: nanoseconds_to_seconds(stop),
+            });
+        }
+    }
+    for sub_entry in &sub_entries {
+        collect_chapters(sub_entry, chapters);
+    }
+}
+
+fn nanoseconds_to_seconds(nanoseconds: i64) -> Option<f64> {
+    // A negative time marks an unknown start or stop:
+    (nanoseconds >= 0).then(|| nanoseconds as f64 / 1_000_000_000_f64)
+}
+
+/// Index of the chapter, which contains the position in seconds.
+pub fn chapter_at(chapters: &[Chapter], position: f64) -> Option<usize> {
+    chapters
+        .iter()
+        .rposition(|chapter| chapter.start <= position && chapter.stop.map_or(true, |stop| position < stop))
+}
+
+/// Seconds after the start of a chapter, in which the previous chapter is selected instead of the current one.
+pub const PREVIOUS_CHAPTER_THRESHOLD: f64 = 3.0;
+
+/// Start of the first chapter after the position in seconds.
+pub fn next_chapter_start(chapters: &[Chapter], position: f64) -> Option<f64> {
+    chapters
+        .iter()
+        .map(|chapter| chapter.start)
+        .find(|start| *start > position)
+}
+
+/// Start of the current chapter, or of the previous chapter if the current chapter started less than
+/// [`PREVIOUS_CHAPTER_THRESHOLD`] seconds before the position.
+///
+/// In the first chapter this is its start, before the first chapter the start of the media. `None` is only returned
+/// without any chapters.
+pub fn previous_chapter_start(chapters: &[Chapter], position: f64) -> Option<f64> {
+    let first = chapters.first()?;
+    let start = chapters
+        .iter()
+        .rev()
+        .map(|chapter| chapter.start)
+        .find(|start| start + PREVIOUS_CHAPTER_THRESHOLD < position);
+    Some(start.unwrap_or(if first.start <= position { first.start } else { 0.0 }))
+}
@@ -0,0 +1,170 @@
+use gst::{glib, prelude::*};
+use std::fmt;
+
+/// Details of an error reported by GStreamer.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ErrorDetails {
+    /// Human readable error message.
+    pub message: String,
+    /// Name of the error domain, e.g. `gst-stream-error-quark`.
+    pub domain: Option<String>,
+    /// Error code within the domain, only known for the GStreamer core domains.
+    pub code: Option<i32>,
+    /// Path of the element that posted the error, e.g. `/GstPlayBin3:playbin3/...`.
+    pub source: Option<String>,
+    /// Additional debug information of the element.
+    pub debug: Option<String>,
+}
+
+impl ErrorDetails {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            ..Default::default()
+        }
+    }
+
+    fn from_glib_error(error: &glib::Error) -> Self {
+        Self {
+            message: error.message().to_string(),
+            domain: Some(error.domain().as_str().to_string()),
+            code: error_code(error),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlayerError {
+    /// A GStreamer plugin required to play the media is not installed.
+    MissingPlugin {
+        /// Installer details of the missing plugins, see `gst_pbutils::missing_plugin_message_get_installer_detail`.
+        installer_details: Vec<String>,
+        details: ErrorDetails,
+    },
+    /// The media type is not supported or the media is malformed.
+    UnsupportedFormat(ErrorDetails),
+    /// A local resource could not be accessed.
+    Resource(ErrorDetails),
+    /// A network resource could not be accessed.
+    Network(ErrorDetails),
+    /// Decoding of the media failed.
+    Decoder(ErrorDetails),
+    InvalidUri(ErrorDetails),
+    Timeout(ErrorDetails),
+    Other(ErrorDetails),
+}
+
+impl PlayerError {
+    pub fn details(&self) -> &ErrorDetails {
+        match self {
+            Self::MissingPlugin { details, .. }
+            | Self::UnsupportedFormat(details)
+            | Self::Resource(details)
+            | Self::Network(details)
+            | Self::Decoder(details)
+            | Self::InvalidUri(details)
+            | Self::Timeout(details)
+            | Self::Other(details) => details,
+        }
+    }
+
+    /// Creates the error from an error or warning message of the pipeline bus.
+    ///
+    /// Returns `None` for all other message types.
+    pub fn from_message(message: &gst::MessageRef, installer_details: Vec<String>) -> Option<Self> {
+        let (error, debug) = match message.view() {
+            gst::MessageView::Error(msg) => (msg.error(), msg.debug()),
+            gst::MessageView::Warning(msg) => (msg.error(), msg.debug()),
+            _ => return None,
+        };
+        let details = ErrorDetails {
+            source: message.src().map(|src| src.path_string().to_string()),
+            debug: debug.map(|x| x.to_string()),
+            ..ErrorDetails::from_glib_error(&error)
+        };
+        let network = message
+            .src()
+            .and_then(|src| src.downcast_ref::<gst::Element>())
+            .and_then(|element| element.factory())
+            .and_then(|factory| {
+                factory
+                    .metadata(gst::ELEMENT_METADATA_KLASS)
+                    .map(|klass| klass.contains("Network"))
+            })
+            .unwrap_or(false);
+
+        Some(Self::classify(&error, details, installer_details, network))
+    }
+
+    fn classify(error: &glib::Error, details: ErrorDetails, installer_details: Vec<String>, network: bool) -> Self {
+        if !installer_details.is_empty()
+            || error.matches(gst::CoreError::MissingPlugin)
+            || error.matches(gst::StreamError::CodecNotFound)
+        {
+            return Self::MissingPlugin {
+                installer_details,
+                details,
+            };
+        }
+        if let Some(kind) = error.kind::<gst::StreamError>() {
+            return match kind {
+                gst::StreamError::Decode | gst::StreamError::Decrypt | gst::StreamError::DecryptNokey => {
+                    Self::Decoder(details)
+                }
+                gst::StreamError::NotImplemented
+                | gst::StreamError::TypeNotFound
+                | gst::StreamError::WrongType
+                | gst::StreamError::Demux
+                | gst::StreamError::Format => Self::UnsupportedFormat(details),
+                _ => Self::Other(details),
+            };
+        }
+        if error.kind::<gst::ResourceError>().is_some() {
+            return if network {
+                Self::Network(details)
+            } else {
+                Self::Resource(details)
+            };
+        }
+        Self::Other(details)
+    }
+}
+
+impl fmt::Display for PlayerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let details = self.details();
+        match &details.source {
+            Some(source) => write!(f, "Error from element {source}: {}", details.message),
+            None => write!(f, "{}", details.message),
+        }
+    }
+}
+
+impl std::error::Error for PlayerError {}
+
+impl From<glib::Error> for PlayerError {
+    fn from(error: glib::Error) -> Self {
+        Self::classify(&error, ErrorDetails::from_glib_error(&error), Vec::new(), false)
+    }
+}
+
+impl From<anyhow::Error> for PlayerError {
+    fn from(error: anyhow::Error) -> Self {
+        match error.downcast::<PlayerError>() {
+            Ok(error) => error,
+            Err(error) => Self::Other(ErrorDetails::new(format!("{error:#}"))),
+        }
+    }
+}
+
+fn error_code(error: &glib::Error) -> Option<i32> {
+    use glib::error::ErrorDomain;
+
+    error
+        .kind::<gst::CoreError>()
+        .map(ErrorDomain::code)
+        .or_else(|| error.kind::<gst::LibraryError>().map(ErrorDomain::code))
+        .or_else(|| error.kind::<gst::ResourceError>().map(ErrorDomain::code))
+        .or_else(|| error.kind::<gst::StreamError>().map(ErrorDomain::code))
+}
@@ -4,11 +4,16 @@ pub extern crate gst_play;
 pub extern crate gst_tag;
 
 pub mod actions;
+pub mod chapter;
+pub mod error;
 pub mod glib_helpers;
 pub mod gst_helpers;
 mod localization;
+pub mod media_directory;
+pub mod media_info;
 pub mod misc;
 pub mod player;
+pub mod playlist_file;
 pub mod ui;
 pub mod uri_helpers;
 
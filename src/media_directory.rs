@@ -0,0 +1,151 @@
+use log::*;
+use std::{
+    cmp::Ordering,
+    collections::HashSet,
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+use crate::playlist_file;
+
+/// Extensions of files, which are known to be playable without inspecting their content.
+const MEDIA_EXTENSIONS: &[&str] = &[
+    "3g2", "3gp", "aac", "aif", "aiff", "asf", "avi", "dv", "flac", "flv", "m2ts", "m2v", "m4a", "m4v", "mka", "mkv",
+    "mov", "mp3", "mp4", "mpeg", "mpg", "mts", "mxf", "oga", "ogg", "ogv", "opus", "ts", "vob", "wav", "webm", "wma",
+    "wmv", "y4m",
+];
+
+/// Media types detected by typefind, which are playable but neither audio nor video media types.
+const MEDIA_CAPS: &[&str] = &[
+    "application/mxf",
+    "application/ogg",
+    "application/x-id3",
+    "application/x-apetag",
+];
+
+/// Number of bytes read from the beginning of a file to detect its media type.
+const TYPEFIND_SIZE: u64 = 64 * 1024;
+
+/// Whether the file can be played, which is checked by the extension and by typefind for unknown extensions.
+///
+/// GStreamer has to be initialized to detect the media type of files with unknown extensions.
+pub fn is_media_file(path: &Path) -> bool {
+    let extension = path.extension().and_then(|extension| extension.to_str());
+    if extension.is_some_and(|extension| MEDIA_EXTENSIONS.contains(&extension.to_lowercase().as_str())) {
+        return true;
+    }
+
+    let mut data = Vec::new();
+    if let Err(error) = std::fs::File::open(path).and_then(|file| file.take(TYPEFIND_SIZE).read_to_end(&mut data)) {
+        warn!("Cannot read file {} to detect its media type: {error}", path.display());
+        return false;
+    }
+    match gst_base::type_find_helper_for_data_with_extension(None::<&gst::Object>, &data, extension) {
+        Ok((caps, probability)) => {
+            let media_type = caps
+                .structure(0)
+                .map(|structure| structure.name().as_str())
+                .unwrap_or_default();
+            trace!(
+                "Detected media type {media_type} ({probability:?}) of file {}",
+                path.display()
+            );
+            media_type.starts_with("video/") || media_type.starts_with("audio/") || MEDIA_CAPS.contains(&media_type)
+        }
+        Err(_) => false,
+    }
+}
+
+/// Collects the playable files of the directory and its subdirectories in natural order.
+///
+/// The depth limits the levels of subdirectories, which are descended into, e.g. `Some(0)` only collects the files
+/// of the directory itself. Hidden files and directories as well as playlist files are skipped.
+pub fn expand_directory(dir: &Path, max_depth: Option<usize>) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let mut visited = HashSet::new();
+    collect_media_files(dir, max_depth, &mut visited, &mut files);
+    debug!("found {} media files in directory {}", files.len(), dir.display());
+    files
+}
+
+fn collect_media_files(dir: &Path, max_depth: Option<usize>, visited: &mut HashSet<PathBuf>, files: &mut Vec<PathBuf>) {
+    // Symbolic links may create cycles:
+    match dir.canonicalize() {
+        Ok(canonical) if !visited.insert(canonical) => return,
+        Ok(_) => (),
+        Err(error) => {
+            warn!("Cannot resolve directory {}: {error}", dir.display());
+            return;
+        }
+    }
+
+    let mut paths = match std::fs::read_dir(dir) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| !entry.file_name().to_string_lossy().starts_with('.'))
+            .map(|entry| entry.path())
+            .collect::<Vec<_>>(),
+        Err(error) => {
+            warn!("Cannot read directory {}: {error}", dir.display());
+            return;
+        }
+    };
+    paths.sort_by(|a, b| natural_cmp(&a.to_string_lossy(), &b.to_string_lossy()));
+
+    for path in paths {
+        if path.is_dir() {
+            match max_depth {
+                Some(0) => trace!("Skip directory {} beyond the maximum depth", path.display()),
+                _ => collect_media_files(&path, max_depth.map(|depth| depth - 1), visited, files),
+            }
+        } else if path.is_file() && !playlist_file::is_playlist_file(&path) && is_media_file(&path) {
+            files.push(path);
+        }
+    }
+}
+
+/// Compares the texts in natural order, i.e. case-insensitively and numbers by their value: `clip9` < `clip10`.
+pub fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let mut a_chunks = Chunks(a);
+    let mut b_chunks = Chunks(b);
+    loop {
+        let ordering = match (a_chunks.next(), b_chunks.next()) {
+            (None, None) => return a.cmp(b),
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(a_chunk), Some(b_chunk)) => {
+                let a_digits = a_chunk.starts_with(|c: char| c.is_ascii_digit());
+                let b_digits = b_chunk.starts_with(|c: char| c.is_ascii_digit());
+                if a_digits && b_digits {
+                    let a_number = a_chunk.trim_start_matches('0');
+                    let b_number = b_chunk.trim_start_matches('0');
+                    a_number.len().cmp(&b_number.len()).then_with(|| a_number.cmp(b_number))
+                } else {
+                    a_chunk.to_lowercase().cmp(&b_chunk.to_lowercase())
+                }
+            }
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+}
+
+/// Splits a text into runs of ASCII digits and runs of other characters.
+struct Chunks<'a>(&'a str);
+
+impl<'a> Iterator for Chunks<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let first = self.0.chars().next()?;
+        let is_digit = first.is_ascii_digit();
+        let end = self
+            .0
+            .find(|c: char| c.is_ascii_digit() != is_digit)
+            .unwrap_or(self.0.len());
+        let (chunk, rest) = self.0.split_at(end);
+        self.0 = rest;
+        Some(chunk)
+    }
+}
@@ -0,0 +1,409 @@
+use anyhow::Result;
+use gst_pbutils::{prelude::*, DiscovererInfo};
+use gst_play::{prelude::*, PlayMediaInfo};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::{
+    chapter::{chapters_from_toc, Chapter},
+    player::clock_time_to_seconds,
+};
+
+/// Description of a media, which can be serialized e.g. to JSON.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MediaInfo {
+    pub uri: String,
+    pub title: Option<String>,
+    /// Container format, e.g. `Quicktime` or `video/quicktime`.
+    pub container: Option<String>,
+    /// Duration in seconds.
+    pub duration: Option<f64>,
+    pub seekable: bool,
+    pub live: bool,
+    pub streams: Vec<StreamInfo>,
+    pub tags: BTreeMap<String, String>,
+    /// Chapters of the table of contents.
+    pub chapters: Vec<Chapter>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StreamType {
+    Video,
+    Audio,
+    Subtitle,
+    #[default]
+    Other,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StreamInfo {
+    pub stream_type: StreamType,
+    /// Index of the stream among the streams of the same type, which selects it by `Track::Stream(index)`.
+    pub index: i32,
+    pub codec: Option<String>,
+    pub language: Option<String>,
+    /// Bitrate in bits per second.
+    pub bitrate: Option<u32>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    /// Frames per second.
+    pub framerate: Option<f64>,
+    pub sample_rate: Option<u32>,
+    pub channels: Option<u32>,
+    pub tags: BTreeMap<String, String>,
+}
+
+impl MediaInfo {
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// Tree of all streams, tags and chapters with English labels, e.g. to log the media info.
+    pub fn to_tree(&self) -> termtree::Tree<String> {
+        self.to_tree_with(&EnglishLabels)
+    }
+
+    /// Tree of all streams, tags and chapters with the given labels, e.g. to show a localized media info.
+    pub fn to_tree_with(&self, labels: &impl MediaInfoLabels) -> termtree::Tree<String> {
+        let mut tree = termtree::Tree::new(labels.uri(&self.uri));
+        if let Some(title) = &self.title {
+            tree.push(termtree::Tree::new(labels.title(title)));
+        }
+        if let Some(container) = &self.container {
+            tree.push(termtree::Tree::new(labels.container(container)));
+        }
+        if let Some(duration) = self.duration {
+            tree.push(termtree::Tree::new(labels.duration(duration)));
+        }
+        tree.push(termtree::Tree::new(labels.live(self.live)));
+        tree.push(termtree::Tree::new(labels.seekable(self.seekable)));
+        for (index, stream) in self.streams.iter().enumerate() {
+            tree.push(stream.to_tree(index, labels));
+        }
+        if !self.chapters.is_empty() {
+            let mut sub_tree = termtree::Tree::new(labels.chapters());
+            for chapter in &self.chapters {
+                let title = chapter.title.as_deref().unwrap_or(&chapter.uid);
+                sub_tree.push(termtree::Tree::new(labels.chapter(title, chapter.start, chapter.stop)));
+            }
+            tree.push(sub_tree);
+        }
+        if !self.tags.is_empty() {
+            tree.push(tags_to_tree(&self.tags, labels));
+        }
+        tree
+    }
+}
+
+impl StreamInfo {
+    fn to_tree(&self, index: usize, labels: &impl MediaInfoLabels) -> termtree::Tree<String> {
+        let mut tree = termtree::Tree::new(labels.stream(index));
+        tree.push(termtree::Tree::new(labels.stream_type(self.stream_type)));
+        if let Some(codec) = &self.codec {
+            tree.push(termtree::Tree::new(labels.codec(codec)));
+        }
+        if let Some(language) = &self.language {
+            tree.push(termtree::Tree::new(labels.language(language)));
+        }
+        if let (Some(width), Some(height)) = (self.width, self.height) {
+            tree.push(termtree::Tree::new(labels.resolution(width, height)));
+        }
+        if let Some(framerate) = self.framerate {
+            tree.push(termtree::Tree::new(labels.framerate(framerate)));
+        }
+        if let Some(sample_rate) = self.sample_rate {
+            tree.push(termtree::Tree::new(labels.sample_rate(sample_rate)));
+        }
+        if let Some(channels) = self.channels {
+            tree.push(termtree::Tree::new(labels.channels(channels)));
+        }
+        if let Some(bitrate) = self.bitrate {
+            tree.push(termtree::Tree::new(labels.bitrate(bitrate)));
+        }
+        if !self.tags.is_empty() {
+            tree.push(tags_to_tree(&self.tags, labels));
+        }
+        tree
+    }
+}
+
+impl std::fmt::Display for MediaInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_tree())
+    }
+}
+
+/// Labels of the lines of the media info tree, see [`MediaInfo::to_tree_with`].
+pub trait MediaInfoLabels {
+    fn uri(&self, uri: &str) -> String;
+    fn title(&self, title: &str) -> String;
+    fn container(&self, container: &str) -> String;
+    /// Duration in seconds.
+    fn duration(&self, duration: f64) -> String;
+    fn live(&self, live: bool) -> String;
+    fn seekable(&self, seekable: bool) -> String;
+    fn stream(&self, index: usize) -> String;
+    fn stream_type(&self, stream_type: StreamType) -> String;
+    fn codec(&self, codec: &str) -> String;
+    fn language(&self, language: &str) -> String;
+    fn resolution(&self, width: u32, height: u32) -> String;
+    fn framerate(&self, framerate: f64) -> String;
+    fn sample_rate(&self, sample_rate: u32) -> String;
+    fn channels(&self, channels: u32) -> String;
+    fn bitrate(&self, bitrate: u32) -> String;
+    /// Heading of the chapters.
+    fn chapters(&self) -> String;
+    /// Chapter with start and stop in seconds.
+    fn chapter(&self, title: &str, start: f64, stop: Option<f64>) -> String;
+    /// Heading of the tags.
+    fn tags(&self) -> String;
+}
+
+/// English labels of [`MediaInfo::to_tree`].
+pub struct EnglishLabels;
+
+impl MediaInfoLabels for EnglishLabels {
+    fn uri(&self, uri: &str) -> String {
+        format!("URI: {uri}")
+    }
+
+    fn title(&self, title: &str) -> String {
+        format!("Title: {title}")
+    }
+
+    fn container(&self, container: &str) -> String {
+        format!("Container: {container}")
+    }
+
+    fn duration(&self, duration: f64) -> String {
+        format!("Duration: {duration:.3} s")
+    }
+
+    fn live(&self, live: bool) -> String {
+        format!("is live: {live}")
+    }
+
+    fn seekable(&self, seekable: bool) -> String {
+        format!("is seekable: {seekable}")
+    }
+
+    fn stream(&self, index: usize) -> String {
+        format!("Stream #{index}")
+    }
+
+    fn stream_type(&self, stream_type: StreamType) -> String {
+        format!("type: {stream_type:?}")
+    }
+
+    fn codec(&self, codec: &str) -> String {
+        format!("codec: {codec}")
+    }
+
+    fn language(&self, language: &str) -> String {
+        format!("language: {language}")
+    }
+
+    fn resolution(&self, width: u32, height: u32) -> String {
+        format!("resolution: {width}x{height}")
+    }
+
+    fn framerate(&self, framerate: f64) -> String {
+        format!("framerate: {framerate:.3} fps")
+    }
+
+    fn sample_rate(&self, sample_rate: u32) -> String {
+        format!("sample rate: {sample_rate} Hz")
+    }
+
+    fn channels(&self, channels: u32) -> String {
+        format!("channels: {channels}")
+    }
+
+    fn bitrate(&self, bitrate: u32) -> String {
+        format!("bitrate: {bitrate} bit/s")
+    }
+
+    fn chapters(&self) -> String {
+        "Chapters:".to_owned()
+    }
+
+    fn chapter(&self, title: &str, start: f64, stop: Option<f64>) -> String {
+        match stop {
+            Some(stop) => format!("{title}: {start:.3} s - {stop:.3} s"),
+            None => format!("{title}: {start:.3} s"),
+        }
+    }
+
+    fn tags(&self) -> String {
+        "Tags:".to_owned()
+    }
+}
+
+fn tags_to_tree(tags: &BTreeMap<String, String>, labels: &impl MediaInfoLabels) -> termtree::Tree<String> {
+    let mut tree = termtree::Tree::new(labels.tags());
+    for (name, value) in tags {
+        tree.push(termtree::Tree::new(format!("{name}: {value}")));
+    }
+    tree
+}
+
+impl From<&DiscovererInfo> for MediaInfo {
+    fn from(info: &DiscovererInfo) -> Self {
+        let tags = info.tags().map(|tags| tags_to_map(&tags)).unwrap_or_default();
+        let container = info
+            .stream_info()
+            .and_then(|info| info.downcast::<gst_pbutils::DiscovererContainerInfo>().ok())
+            .and_then(|info| {
+                info.tags()
+                    .and_then(|tags| tags.get::<gst::tags::ContainerFormat>().map(|x| x.get().to_string()))
+                    .or_else(|| caps_name(info.caps()))
+            });
+
+        Self {
+            uri: info.uri().to_string(),
+            title: tags.get(gst::tags::Title::TAG_NAME.as_str()).cloned(),
+            container,
+            duration: info.duration().map(clock_time_to_seconds),
+            seekable: info.is_seekable(),
+            live: info.is_live(),
+            streams: index_streams(info.stream_list().iter().map(discoverer_stream_info).collect()),
+            tags,
+            chapters: info.toc().map(|toc| chapters_from_toc(&toc)).unwrap_or_default(),
+        }
+    }
+}
+
+/// Numbers the streams of each type in the order of the discoverer, which is the order of the player.
+fn index_streams(mut streams: Vec<StreamInfo>) -> Vec<StreamInfo> {
+    for position in 0..streams.len() {
+        let stream_type = streams[position].stream_type;
+        streams[position].index = streams[..position]
+            .iter()
+            .filter(|stream| stream.stream_type == stream_type)
+            .count() as i32;
+    }
+    streams
+}
+
+fn discoverer_stream_info(info: &gst_pbutils::DiscovererStreamInfo) -> StreamInfo {
+    let tags = info.tags().map(|tags| tags_to_map(&tags)).unwrap_or_default();
+    let codec = |tag_name: &str| tags.get(tag_name).cloned().or_else(|| caps_name(info.caps()));
+
+    if let Some(video) = info.downcast_ref::<gst_pbutils::DiscovererVideoInfo>() {
+        StreamInfo {
+            stream_type: StreamType::Video,
+            codec: codec(gst::tags::VideoCodec::TAG_NAME),
+            bitrate: known_bitrate(video.bitrate()),
+            width: Some(video.width()),
+            height: Some(video.height()),
+            framerate: fraction_to_f64(video.framerate()),
+            tags,
+            ..Default::default()
+        }
+    } else if let Some(audio) = info.downcast_ref::<gst_pbutils::DiscovererAudioInfo>() {
+        StreamInfo {
+            stream_type: StreamType::Audio,
+            codec: codec(gst::tags::AudioCodec::TAG_NAME),
+            language: audio.language().map(|x| x.to_string()),
+            bitrate: known_bitrate(audio.bitrate()),
+            sample_rate: Some(audio.sample_rate()),
+            channels: Some(audio.channels()),
+            tags,
+            ..Default::default()
+        }
+    } else if let Some(subtitle) = info.downcast_ref::<gst_pbutils::DiscovererSubtitleInfo>() {
+        StreamInfo {
+            stream_type: StreamType::Subtitle,
+            codec: codec(gst::tags::SubtitleCodec::TAG_NAME),
+            language: subtitle.language().map(|x| x.to_string()),
+            tags,
+            ..Default::default()
+        }
+    } else {
+        StreamInfo {
+            codec: codec(gst::tags::Codec::TAG_NAME),
+            tags,
+            ..Default::default()
+        }
+    }
+}
+
+impl From<&PlayMediaInfo> for MediaInfo {
+    fn from(info: &PlayMediaInfo) -> Self {
+        let mut streams: Vec<_> = info
+            .video_streams()
+            .iter()
+            .map(|video| StreamInfo {
+                stream_type: StreamType::Video,
+                index: video.index(),
+                codec: video.codec().map(|x| x.to_string()),
+                bitrate: u32::try_from(video.bitrate()).ok().and_then(known_bitrate),
+                width: u32::try_from(video.width()).ok(),
+                height: u32::try_from(video.height()).ok(),
+                framerate: fraction_to_f64(video.framerate()),
+                tags: video.tags().map(|tags| tags_to_map(&tags)).unwrap_or_default(),
+                ..Default::default()
+            })
+            .collect();
+        streams.extend(info.audio_streams().iter().map(|audio| StreamInfo {
+            stream_type: StreamType::Audio,
+            index: audio.index(),
+            codec: audio.codec().map(|x| x.to_string()),
+            language: audio.language().map(|x| x.to_string()),
+            bitrate: u32::try_from(audio.bitrate()).ok().and_then(known_bitrate),
+            sample_rate: u32::try_from(audio.sample_rate()).ok(),
+            channels: u32::try_from(audio.channels()).ok(),
+            tags: audio.tags().map(|tags| tags_to_map(&tags)).unwrap_or_default(),
+            ..Default::default()
+        }));
+        streams.extend(info.subtitle_streams().iter().map(|subtitle| StreamInfo {
+            stream_type: StreamType::Subtitle,
+            index: subtitle.index(),
+            codec: subtitle.codec().map(|x| x.to_string()),
+            language: subtitle.language().map(|x| x.to_string()),
+            tags: subtitle.tags().map(|tags| tags_to_map(&tags)).unwrap_or_default(),
+            ..Default::default()
+        }));
+
+        Self {
+            uri: info.uri().to_string(),
+            title: info.title().map(|x| x.to_string()),
+            container: info.container_format().map(|x| x.to_string()),
+            duration: info.duration().map(clock_time_to_seconds),
+            seekable: info.is_seekable(),
+            live: info.is_live(),
+            streams,
+            tags: info.tags().map(|tags| tags_to_map(&tags)).unwrap_or_default(),
+            // The table of contents is not part of the media info of the player, it is added from
+            // `Player::chapters` by the player component:
+            chapters: Vec::new(),
+        }
+    }
+}
+
+/// Converts all tags, which can be represented as a string.
+fn tags_to_map(tags: &gst::TagListRef) -> BTreeMap<String, String> {
+    tags.iter()
+        .filter_map(|(name, value)| {
+            let value = value.transform::<String>().ok()?.get::<String>().ok()?;
+            Some((name.to_string(), value))
+        })
+        .collect()
+}
+
+fn caps_name(caps: Option<gst::Caps>) -> Option<String> {
+    caps.and_then(|caps| caps.structure(0).map(|structure| structure.name().to_string()))
+}
+
+fn known_bitrate(bitrate: u32) -> Option<u32> {
+    (bitrate > 0).then_some(bitrate)
+}
+
+fn fraction_to_f64(fraction: gst::Fraction) -> Option<f64> {
+    (fraction.numer() > 0 && fraction.denom() > 0).then(|| fraction.numer() as f64 / fraction.denom() as f64)
+}
@@ -1,20 +1,126 @@
 use anyhow::{Context, Result};
+use futures_channel::oneshot;
 use gst::{event::Step, format::Buffers, glib, prelude::*};
 use gst_play::PlayMessage;
 use log::*;
-use mxl_relm4_components::relm4::{self, gtk::gdk, Sender};
-use std::sync::{Arc, Mutex};
+use mxl_relm4_components::relm4::{self, gtk::gdk};
+use std::{
+    collections::VecDeque,
+    future::Future,
+    path::Path,
+    sync::{mpsc, Arc, Mutex},
+    time::Instant,
+};
 
 use glib::clone;
 
-use crate::ui::player::messages::{PlaybackState, PlayerComponentCommand, Track};
+use crate::{
+    chapter::{self, Chapter},
+    error::PlayerError,
+    ui::player::messages::{
+        AudioLevel, ColorBalanceChannel, DeinterlaceMode, PlaybackState, PlayerComponentCommand, SnapshotFormat,
+        Statistics, Track, TrickMode,
+    },
+};
+
+/// Lowest supported playback speed, i.e. the fastest reverse playback.
+pub const SPEED_MIN: f64 = -16.0;
+/// Highest supported playback speed.
+pub const SPEED_MAX: f64 = 16.0;
 
 const GLSINKBIN_NAME: &str = "glsinkbin";
+const DEINTERLACE_FACTORY_NAME: &str = "deinterlace";
+const AUDIO_LEVEL_INTERVAL: gst::ClockTime = gst::ClockTime::from_mseconds(100);
+const GAMMA_MIN: f64 = 0.01;
+const GAMMA_MAX: f64 = 10.0;
+/// Classified errors, which are kept until the player forwards them.
+const MAX_QUEUED_ERRORS: usize = 16;
+
+fn seconds_to_clock_time(seconds: f64) -> gst::ClockTime {
+    gst::ClockTime::from_mseconds((seconds * 1000_f64) as u64)
+}
+
+pub(crate) fn clock_time_to_seconds(time: gst::ClockTime) -> f64 {
+    time.mseconds() as f64 / 1000_f64
+}
+
+/// Consumer of the events emitted by a [`Player`].
+///
+/// The events are emitted from the thread that runs the main context of the player and from internal
+/// GStreamer threads, so the consumer has to be [`Send`].
+pub struct PlayerEventSink(Box<dyn Fn(PlayerComponentCommand) + Send>);
+
+impl PlayerEventSink {
+    pub fn from_fn<F>(func: F) -> Self
+    where
+        F: Fn(PlayerComponentCommand) + Send + 'static,
+    {
+        Self(Box::new(func))
+    }
+
+    fn send(&self, cmd: PlayerComponentCommand) {
+        (self.0)(cmd)
+    }
+}
+
+impl std::fmt::Debug for PlayerEventSink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PlayerEventSink").finish_non_exhaustive()
+    }
+}
+
+impl From<relm4::Sender<PlayerComponentCommand>> for PlayerEventSink {
+    fn from(sender: relm4::Sender<PlayerComponentCommand>) -> Self {
+        Self::from_fn(move |cmd| sender.send(cmd).unwrap_or_default())
+    }
+}
+
+impl From<mpsc::Sender<PlayerComponentCommand>> for PlayerEventSink {
+    fn from(sender: mpsc::Sender<PlayerComponentCommand>) -> Self {
+        Self::from_fn(move |cmd| sender.send(cmd).unwrap_or_default())
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum VideoSink {
+    /// Render into a `gdk::Paintable`, which requires a GTK display.
+    #[default]
+    Gtk,
+    /// Discard all video frames, e.g. for tests or command-line tools.
+    Fake,
+    /// Hand all video frames to an `appsink`, which can be retrieved by [`Player::video_sink`].
+    App,
+}
+
+/// An element or a bin to process the audio or video data of the player.
+#[derive(Debug, Clone)]
+pub enum FilterSpec {
+    Element(gst::Element),
+    /// A bin described in `gst-launch` syntax, e.g. `videoflip method=clockwise ! videobalance`.
+    Launch(String),
+}
+
+impl FilterSpec {
+    fn build(&self) -> Result<gst::Element> {
+        match self {
+            Self::Element(element) => Ok(element.clone()),
+            Self::Launch(description) => Ok(gst::parse::bin_from_description(description, true)
+                .with_context(|| format!("Failed to create filter from '{description}'"))?
+                .upcast()),
+        }
+    }
+}
 
 #[derive(Debug)]
 pub struct PlayerBuilder {
     seek_accurate: bool,
+    video_sink: VideoSink,
     compositor: Option<gst::Element>,
+    video_filter: Option<FilterSpec>,
+    audio_filter: Option<FilterSpec>,
+    audio_level_meter: bool,
+    gamma: bool,
+    preserve_pitch: bool,
     audio_offset: i64,
     subtitle_offset: i64,
 }
@@ -29,7 +135,13 @@ impl PlayerBuilder {
     pub fn new() -> Self {
         Self {
             seek_accurate: false,
+            video_sink: VideoSink::default(),
             compositor: None,
+            video_filter: None,
+            audio_filter: None,
+            audio_level_meter: false,
+            gamma: false,
+            preserve_pitch: false,
             audio_offset: 0,
             subtitle_offset: 0,
         }
@@ -40,11 +152,44 @@ impl PlayerBuilder {
         self
     }
 
+    pub fn video_sink(&mut self, video_sink: VideoSink) -> &mut Self {
+        self.video_sink = video_sink;
+        self
+    }
+
     pub fn compositor(&mut self, compositor: Option<gst::Element>) -> &mut Self {
         self.compositor = compositor;
         self
     }
 
+    pub fn video_filter(&mut self, filter: Option<FilterSpec>) -> &mut Self {
+        self.video_filter = filter;
+        self
+    }
+
+    pub fn audio_filter(&mut self, filter: Option<FilterSpec>) -> &mut Self {
+        self.audio_filter = filter;
+        self
+    }
+
+    /// Measure the audio level of each channel, which is reported by [`PlayerComponentCommand::AudioLevel`].
+    pub fn audio_level_meter(&mut self, audio_level_meter: bool) -> &mut Self {
+        self.audio_level_meter = audio_level_meter;
+        self
+    }
+
+    /// Insert a gamma correction into the video path, which is adjusted by [`Player::set_gamma`].
+    pub fn gamma(&mut self, gamma: bool) -> &mut Self {
+        self.gamma = gamma;
+        self
+    }
+
+    /// Keep the audio pitch constant at speeds other than 1.0, see [`Player::set_preserve_pitch`].
+    pub fn preserve_pitch(&mut self, preserve_pitch: bool) -> &mut Self {
+        self.preserve_pitch = preserve_pitch;
+        self
+    }
+
     pub fn audio_offset(&mut self, offset: i64) -> &mut Self {
         self.audio_offset = offset;
         self
@@ -55,7 +200,7 @@ impl PlayerBuilder {
         self
     }
 
-    pub fn build(&self, sender: relm4::Sender<PlayerComponentCommand>) -> Result<Player> {
+    fn build_gtk_sink() -> Result<(gst::Element, gst::Element)> {
         let gtk_sink = gst::ElementFactory::make("gtk4paintablesink").build()?;
 
         let paintable = gtk_sink.property::<gdk::Paintable>("paintable");
@@ -75,6 +220,27 @@ impl PlayerBuilder {
             gtk_sink.clone()
         };
 
+        Ok((video_sink, gtk_sink))
+    }
+
+    fn build_headless_sink(factory_name: &str) -> Result<gst::Element> {
+        debug!("Use {factory_name} for headless playback");
+        gst::ElementFactory::make(factory_name)
+            .property("sync", true)
+            .build()
+            .with_context(|| format!("Failed to create player with {factory_name} video sink"))
+    }
+
+    pub fn build(&self, event_sink: impl Into<PlayerEventSink>) -> Result<Player> {
+        let (video_sink, gtk_sink) = match self.video_sink {
+            VideoSink::Gtk => {
+                let (video_sink, gtk_sink) = Self::build_gtk_sink()?;
+                (video_sink, Some(gtk_sink))
+            }
+            VideoSink::Fake => (Self::build_headless_sink("fakesink")?, None),
+            VideoSink::App => (Self::build_headless_sink("appsink")?, None),
+        };
+
         let renderer = gst_play::PlayVideoOverlayVideoRenderer::with_sink(&video_sink);
 
         let gst_play = gst_play::Play::new(Some(renderer.clone().upcast::<gst_play::PlayVideoRenderer>()));
@@ -87,6 +253,37 @@ impl PlayerBuilder {
         if let Some(compositor) = &self.compositor {
             pipeline.set_property("video-stream-combiner", compositor);
         }
+        let filters = Filters {
+            video: self.video_filter.as_ref().map(FilterSpec::build).transpose()?,
+            audio: self.audio_filter.as_ref().map(FilterSpec::build).transpose()?,
+            gamma: if self.gamma {
+                Some(
+                    gst::ElementFactory::make("gamma")
+                        .build()
+                        .with_context(|| "Failed to create gamma correction")?,
+                )
+            } else {
+                None
+            },
+            scaletempo: if self.preserve_pitch {
+                Some(build_scaletempo()?)
+            } else {
+                None
+            },
+            level: if self.audio_level_meter {
+                Some(
+                    gst::ElementFactory::make("level")
+                        .property("interval", AUDIO_LEVEL_INTERVAL.nseconds())
+                        .property("post-messages", true)
+                        .build()
+                        .with_context(|| "Failed to create audio level meter")?,
+                )
+            } else {
+                None
+            },
+        };
+        filters.apply(&pipeline)?;
+        let gamma = filters.gamma.clone();
 
         let mut config = gst_play.config();
         config.set_seek_accurate(self.seek_accurate);
@@ -96,10 +293,40 @@ impl PlayerBuilder {
             .with_context(|| "Failed to set player configuration")?;
 
         let player_data = Arc::new(Mutex::new(PlayerData {
-            sender,
+            event_sink: event_sink.into(),
             current_state: None,
+            loop_region: LoopRegion::default(),
+            rearm_loop: false,
+            trick_mode: TrickMode::default(),
+            rearm_trick_mode: false,
+            pending_operations: Vec::new(),
+            missing_plugins: Vec::new(),
+            errors: VecDeque::new(),
+            warnings: VecDeque::new(),
+            statistics_sample: None,
+            deinterlace_mode: DeinterlaceMode::default(),
+            filters,
+            filter_restore: None,
+            chapters: Vec::new(),
+            current_uri: None,
+            next_uri: None,
+            gapless_uri: None,
         }));
 
+        if let Some(bin) = pipeline.downcast_ref::<gst::Bin>() {
+            // The deinterlace element is created by playsink on demand, so apply the selected mode when it is added:
+            bin.connect_deep_element_added(clone!(
+                #[weak]
+                player_data,
+                move |_, _, element| {
+                    if is_deinterlacer(element) {
+                        let mode = player_data.lock().unwrap().deinterlace_mode;
+                        set_deinterlace_mode(element, mode);
+                    }
+                }
+            ));
+        }
+
         let _bus_watch = gst_play
             .message_bus()
             .add_watch_local(clone!(
@@ -112,14 +339,21 @@ impl PlayerBuilder {
                 move |_, message| {
                     match PlayMessage::parse(message) {
                         Ok(PlayMessage::EndOfStream) => {
-                            if let Some(uri) = gst_play.uri() {
-                                let player_data = player_data.lock().unwrap();
-                                player_data.send(PlayerComponentCommand::EndOfStream(uri.into()));
+                            let player_data = player_data.lock().unwrap();
+                            // The player does not know about URIs switched gaplessly by the playbin:
+                            if let Some(uri) = player_data
+                                .current_uri
+                                .clone()
+                                .or_else(|| gst_play.uri().map(Into::into))
+                            {
+                                player_data.send(PlayerComponentCommand::EndOfStream(uri));
                             }
                         }
                         Ok(PlayMessage::MediaInfoUpdated { info }) => {
                             let mut player_data_guard = player_data.as_ref().lock();
                             let player_data = player_data_guard.as_mut().unwrap();
+                            player_data
+                                .complete_pending_operations(PendingEvent::MediaInfoUpdated(info.uri().to_string()));
                             player_data.send(PlayerComponentCommand::MediaInfoUpdated(info));
                         }
                         Ok(PlayMessage::DurationChanged { duration }) => {
@@ -132,7 +366,8 @@ impl PlayerBuilder {
                         }
                         Ok(PlayMessage::PositionUpdated { position }) => {
                             let player_data = player_data.lock().unwrap();
-                            if let Some(position) = position {
+                            // The position jumps while the player is restarted to replace the filters:
+                            if let Some(position) = position.filter(|_| player_data.filter_restore.is_none()) {
                                 player_data.send(PlayerComponentCommand::PositionUpdated(
                                     position.mseconds() as f64 / 1000_f64,
                                 ));
@@ -155,25 +390,61 @@ impl PlayerBuilder {
                             };
                             if let Some(s) = state {
                                 let mut player_data = player_data.lock().unwrap();
-                                player_data.change_state(s);
+                                if player_data.filter_restore.is_some() {
+                                    // The player is only restarted to replace the filters, which is not reported:
+                                    if let Some(restore) = player_data.continue_filter_restore(&gst_play.pipeline(), s)
+                                    {
+                                        drop(player_data);
+                                        restore.apply(&gst_play);
+                                    }
+                                } else {
+                                    player_data.change_state(s);
+                                }
                             }
                         }
+                        Ok(PlayMessage::Buffering { percent }) => {
+                            let player_data = player_data.lock().unwrap();
+                            player_data.send(PlayerComponentCommand::Buffering(percent));
+                        }
                         Ok(PlayMessage::VolumeChanged { volume }) => {
                             let player_data = player_data.lock().unwrap();
                             player_data.send(PlayerComponentCommand::VolumeChanged(volume));
                         }
                         Ok(PlayMessage::Error { error, .. }) => {
                             let mut player_data = player_data.lock().unwrap();
+                            let error = take_classified_error(&mut player_data.errors, error);
+                            player_data.filter_restore = None;
                             player_data.change_state(PlaybackState::Error);
-                            player_data.send(PlayerComponentCommand::Error(anyhow::anyhow!(error)));
+                            player_data.fail_pending_operations(|| error.clone().into());
+                            player_data.send(PlayerComponentCommand::Error(error));
                         }
                         Ok(PlayMessage::SeekDone) => {
-                            let player_data = player_data.lock().unwrap();
+                            let mut player_data = player_data.lock().unwrap();
+                            let rearm_loop = std::mem::take(&mut player_data.rearm_loop);
+                            let rearm_trick_mode = std::mem::take(&mut player_data.rearm_trick_mode);
+                            let loop_region = player_data.loop_region.active();
+                            let trick_mode = player_data.trick_mode;
+                            player_data.complete_pending_operations(PendingEvent::SeekDone);
                             player_data.send(PlayerComponentCommand::SeekDone);
+                            drop(player_data);
+                            if let Some(loop_region) = loop_region.filter(|_| rearm_loop) {
+                                // The seek of the player left the segment mode, re-enter it at the current position:
+                                let position = gst_play.position();
+                                if let Err(error) = seek_loop_region(&gst_play, loop_region, position, trick_mode, true)
+                                {
+                                    warn!("Cannot re-enter loop region after seek: {error}");
+                                }
+                            } else if rearm_trick_mode && gst_play.rate() != 1.0 {
+                                // The seek of the player decodes all frames, continue with key units only:
+                                if let Err(error) = seek_key_units(&gst_play) {
+                                    warn!("Cannot enter key unit trick mode after seek: {error}");
+                                }
+                            }
                         }
                         Ok(PlayMessage::Warning { error, .. }) => {
-                            let player_data = player_data.lock().unwrap();
-                            player_data.send(PlayerComponentCommand::Warning(anyhow::anyhow!(error)));
+                            let mut player_data = player_data.lock().unwrap();
+                            let error = take_classified_error(&mut player_data.warnings, error);
+                            player_data.send(PlayerComponentCommand::Warning(error));
                         }
                         _ => (),
                     }
@@ -183,6 +454,164 @@ impl PlayerBuilder {
             ))
             .with_context(|| "Cannot add watcher to player bus")?;
 
+        // Hand over the next URI before the current one ends, so the playbin switches to it without a gap. The signal is
+        // emitted from a streaming thread:
+        pipeline.connect(
+            "about-to-finish",
+            false,
+            clone!(
+                #[weak]
+                player_data,
+                #[upgrade_or]
+                None,
+                move |values| {
+                    let mut player_data = player_data.lock().unwrap();
+                    if player_data.loop_region.active().is_none() {
+                        if let Some(uri) = player_data.next_uri.take() {
+                            match values[0].get::<gst::Element>() {
+                                Ok(playbin) => {
+                                    debug!("player about to finish, continue with uri {uri}");
+                                    // The player has no API for gapless playback, setting its URI stops the
+                                    // playback. So the URI is handed to the playbin directly, which leaves the
+                                    // player reporting the previous URI. `Player::uri` and the end of stream
+                                    // report the URI of the stream start instead:
+                                    playbin.set_property("uri", &uri);
+                                    player_data.gapless_uri = Some(uri);
+                                }
+                                Err(error) => warn!("Cannot continue gaplessly with uri {uri}: {error}"),
+                            }
+                        }
+                    }
+                    None
+                }
+            ),
+        );
+
+        if let Some(bus) = gst_play.pipeline().bus() {
+            bus.connect_message(
+                Some("stream-start"),
+                clone!(
+                    #[weak]
+                    player_data,
+                    move |_, _| {
+                        let mut player_data = player_data.lock().unwrap();
+                        if let Some(uri) = player_data.gapless_uri.take() {
+                            debug!("player switched gaplessly to uri {uri}");
+                            let had_chapters = !player_data.chapters.is_empty();
+                            let had_loop_region = player_data.loop_region.active().is_some();
+                            player_data.reset_media();
+                            player_data.current_uri = Some(uri.clone());
+                            if had_chapters {
+                                player_data.send(PlayerComponentCommand::ChaptersChanged(Vec::new()));
+                            }
+                            if had_loop_region {
+                                player_data.send(PlayerComponentCommand::LoopRegionChanged(None));
+                            }
+                            player_data.send(PlayerComponentCommand::GaplessSwitched(uri));
+                        }
+                    }
+                ),
+            );
+            bus.connect_message(
+                Some("toc"),
+                clone!(
+                    #[weak]
+                    player_data,
+                    move |_, message| {
+                        if let gst::MessageView::Toc(toc) = message.view() {
+                            let (toc, _) = toc.toc();
+                            let chapters = chapter::chapters_from_toc(&toc);
+                            trace!("chapters of media: {chapters:?}");
+                            let mut player_data = player_data.lock().unwrap();
+                            if player_data.chapters != chapters {
+                                player_data.chapters = chapters.clone();
+                                player_data.send(PlayerComponentCommand::ChaptersChanged(chapters));
+                            }
+                        }
+                    }
+                ),
+            );
+            if self.audio_level_meter {
+                bus.connect_message(
+                    Some("element"),
+                    clone!(
+                        #[weak]
+                        player_data,
+                        move |_, message| {
+                            if let Some(level) = message
+                                .structure()
+                                .filter(|structure| structure.name() == "level")
+                                .and_then(parse_audio_level)
+                            {
+                                player_data
+                                    .lock()
+                                    .unwrap()
+                                    .send(PlayerComponentCommand::AudioLevel(level));
+                            }
+                        }
+                    ),
+                );
+            }
+
+            // Segment seeks post a segment-done message instead of EOS, which is used to restart the loop region
+            // without a flush. The message is handled on the internal thread of the player:
+            bus.connect_message(
+                Some("segment-done"),
+                clone!(
+                    #[weak]
+                    gst_play,
+                    #[weak]
+                    player_data,
+                    move |_, _| {
+                        let (loop_region, trick_mode) = {
+                            let player_data = player_data.lock().unwrap();
+                            (player_data.loop_region.active(), player_data.trick_mode)
+                        };
+                        if let Some(loop_region) = loop_region {
+                            trace!("end of loop region reached, restart loop region {loop_region:?}");
+                            if let Err(error) = seek_loop_region(&gst_play, loop_region, None, trick_mode, false) {
+                                warn!("Cannot restart loop region: {error}");
+                            }
+                        }
+                    }
+                ),
+            );
+
+            // The player only reports the message of pipeline errors, so keep the original error domain, source
+            // and missing plugins. Sync messages are emitted before the player forwards the error to its own bus:
+            bus.enable_sync_message_emission();
+            bus.connect_sync_message(
+                None,
+                clone!(
+                    #[weak]
+                    player_data,
+                    move |_, message| match message.view() {
+                        gst::MessageView::Element(_) if gst_pbutils::is_missing_plugin_message(message) => {
+                            if let Some(detail) = gst_pbutils::missing_plugin_message_get_installer_detail(message) {
+                                debug!("missing plugin: {detail}");
+                                player_data.lock().unwrap().missing_plugins.push(detail.to_string());
+                            }
+                        }
+                        gst::MessageView::Error(_) => {
+                            let mut player_data = player_data.lock().unwrap();
+                            let installer_details = std::mem::take(&mut player_data.missing_plugins);
+                            if let Some(error) = PlayerError::from_message(message, installer_details) {
+                                queue_classified_error(&mut player_data.errors, error);
+                            }
+                        }
+                        gst::MessageView::Warning(_) => {
+                            let mut player_data = player_data.lock().unwrap();
+                            let installer_details = std::mem::take(&mut player_data.missing_plugins);
+                            if let Some(warning) = PlayerError::from_message(message, installer_details) {
+                                queue_classified_error(&mut player_data.warnings, warning);
+                            }
+                        }
+                        _ => (),
+                    }
+                ),
+            );
+        }
+
         gst_play.connect_audio_video_offset_notify(clone!(
             #[weak]
             player_data,
@@ -208,7 +637,10 @@ impl PlayerBuilder {
         let player = Player {
             player: gst_play,
             renderer,
+            video_sink,
             gtk_sink,
+            compositor: self.compositor.clone(),
+            gamma,
             _bus_watch,
             data: player_data,
         };
@@ -224,18 +656,357 @@ impl PlayerBuilder {
 pub struct Player {
     player: gst_play::Play,
     renderer: gst_play::PlayVideoOverlayVideoRenderer,
-    gtk_sink: gst::Element,
+    video_sink: gst::Element,
+    gtk_sink: Option<gst::Element>,
+    compositor: Option<gst::Element>,
+    gamma: Option<gst::Element>,
     _bus_watch: gst::bus::BusWatchGuard,
     data: Arc<Mutex<PlayerData>>,
 }
 
 #[derive(Debug)]
 struct PlayerData {
-    sender: Sender<PlayerComponentCommand>,
+    event_sink: PlayerEventSink,
     current_state: Option<PlaybackState>,
+    loop_region: LoopRegion,
+    rearm_loop: bool,
+    trick_mode: TrickMode,
+    rearm_trick_mode: bool,
+    pending_operations: Vec<PendingOperation>,
+    missing_plugins: Vec<String>,
+    /// Errors classified by the sync handler, until the player forwards them to the bus watch.
+    errors: VecDeque<PlayerError>,
+    /// Warnings classified by the sync handler, until the player forwards them to the bus watch.
+    warnings: VecDeque<PlayerError>,
+    statistics_sample: Option<(u64, Instant)>,
+    deinterlace_mode: DeinterlaceMode,
+    filters: Filters,
+    filter_restore: Option<FilterRestore>,
+    chapters: Vec<Chapter>,
+    current_uri: Option<String>,
+    /// URI to continue with gaplessly, when the current URI is about to finish.
+    next_uri: Option<String>,
+    /// URI, which was handed over to the playbin and starts with the next stream.
+    gapless_uri: Option<String>,
+}
+
+/// The filter elements of the player, which are combined into the video and audio filter chain of the playbin.
+#[derive(Debug, Clone)]
+struct Filters {
+    video: Option<gst::Element>,
+    audio: Option<gst::Element>,
+    gamma: Option<gst::Element>,
+    scaletempo: Option<gst::Element>,
+    level: Option<gst::Element>,
+}
+
+impl Filters {
+    fn apply(&self, pipeline: &gst::Element) -> Result<()> {
+        pipeline.set_property("video-filter", filter_chain([&self.gamma, &self.video])?);
+        pipeline.set_property(
+            "audio-filter",
+            filter_chain([&self.scaletempo, &self.level, &self.audio])?,
+        );
+        Ok(())
+    }
+}
+
+/// Playback state to restore after the filters of a running pipeline were replaced.
+///
+/// The filters can only be replaced in a stopped pipeline. The player is stopped and restarted without reporting the
+/// intermediate states, until the playback reached the target state again.
+#[derive(Debug, Clone)]
+struct FilterRestore {
+    /// The state to restart the playback in, which follows play, pause and stop requests during the restore.
+    state: PlaybackState,
+    position: Option<gst::ClockTime>,
+    /// The filters, which are applied once the pipeline stopped, or `None` while the playback is restarted.
+    filters: Option<Filters>,
+}
+
+impl FilterRestore {
+    fn apply(&self, play: &gst_play::Play) {
+        debug!(
+            "restore player state {:?} at {:?} after filter change",
+            self.state, self.position
+        );
+        match self.state {
+            PlaybackState::Paused => play.pause(),
+            _ => play.play(),
+        }
+        if let Some(position) = self.position {
+            play.seek(position);
+        }
+    }
+}
+
+fn build_scaletempo() -> Result<gst::Element> {
+    gst::ElementFactory::make("scaletempo")
+        .build()
+        .with_context(|| "Failed to create element to preserve the audio pitch")
+}
+
+/// Links the elements into a new bin, because the filter properties of the playbin take a single element.
+///
+/// The elements are removed from a previous chain, so the same elements can be used for several chains.
+fn filter_chain<'a>(elements: impl IntoIterator<Item = &'a Option<gst::Element>>) -> Result<Option<gst::Element>> {
+    let elements: Vec<_> = elements.into_iter().flatten().collect();
+    let (Some(first), Some(last)) = (elements.first(), elements.last()) else {
+        return Ok(None);
+    };
+    let bin = gst::Bin::new();
+    for element in &elements {
+        if let Some(parent) = element.parent().and_downcast::<gst::Bin>() {
+            parent.remove(*element)?;
+        }
+        bin.add(*element)?;
+    }
+    gst::Element::link_many(elements.iter().copied())?;
+    let sink = first.static_pad("sink").with_context(|| "Filter has no sink pad")?;
+    let src = last.static_pad("src").with_context(|| "Filter has no src pad")?;
+    bin.add_pad(&gst::GhostPad::with_target(&sink)?)?;
+    bin.add_pad(&gst::GhostPad::with_target(&src)?)?;
+
+    Ok(Some(bin.upcast()))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PendingEvent {
+    SeekDone,
+    StateChanged(PlaybackState),
+    /// The media info of the URI is known.
+    MediaInfoUpdated(String),
+}
+
+#[derive(Debug)]
+struct PendingOperation {
+    event: PendingEvent,
+    sender: oneshot::Sender<Result<()>>,
+}
+
+fn queue_classified_error(queue: &mut VecDeque<PlayerError>, error: PlayerError) {
+    if queue.len() == MAX_QUEUED_ERRORS {
+        queue.pop_front();
+    }
+    queue.push_back(error);
+}
+
+/// Takes the classified error, which the player forwarded as `error`, or classifies `error` itself.
+///
+/// The player forwards the error in its own domain, its message starts with the path of the source element and the
+/// generic message of the original domain and code, followed by the message of the element. Entries of other
+/// sources, domains or codes are kept, until the player forwards them or the media changes.
+fn take_classified_error(queue: &mut VecDeque<PlayerError>, error: glib::Error) -> PlayerError {
+    let message = error.message();
+    queue
+        .iter()
+        .position(|entry| is_forwarded_error(entry, message))
+        .and_then(|position| queue.remove(position))
+        .unwrap_or_else(|| error.into())
+}
+
+fn is_forwarded_error(entry: &PlayerError, message: &str) -> bool {
+    let details = entry.details();
+    let (Some(source), Some(domain)) = (&details.source, &details.domain) else {
+        return false;
+    };
+    let Some(generic_message) = generic_error_message(domain, details.code.unwrap_or_default()) else {
+        return false;
+    };
+    // The message of the element may span several lines and is followed by the debug information, if any:
+    let Some((header, rest)) = message.split_once('\n') else {
+        return false;
+    };
+    header.ends_with(&format!(" from element {source}: {generic_message}"))
+        && rest
+            .strip_prefix(details.message.as_str())
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('\n'))
+}
+
+/// The generic message of a GStreamer error domain and code, see `gst_error_get_message`.
+fn generic_error_message(domain: &str, code: i32) -> Option<String> {
+    use glib::translate::{from_glib_full, IntoGlib};
+
+    let domain = glib::Quark::try_from_str(domain)?;
+    // SAFETY: The function returns a newly allocated string, which is owned by the caller.
+    unsafe { from_glib_full(gst::ffi::gst_error_get_message(domain.into_glib(), code)) }
+}
+
+async fn wait_for_operation(receiver: oneshot::Receiver<Result<()>>) -> Result<()> {
+    receiver
+        .await
+        .unwrap_or_else(|_| Err(anyhow::anyhow!("Player was dropped before the operation finished")))
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+struct LoopRegion {
+    start: Option<gst::ClockTime>,
+    end: Option<gst::ClockTime>,
+}
+
+impl LoopRegion {
+    fn active(&self) -> Option<(gst::ClockTime, gst::ClockTime)> {
+        match (self.start, self.end) {
+            (Some(start), Some(end)) if start < end => Some((start, end)),
+            _ => None,
+        }
+    }
+}
+
+fn is_deinterlacer(element: &gst::Element) -> bool {
+    element
+        .factory()
+        .is_some_and(|factory| factory.name() == DEINTERLACE_FACTORY_NAME)
+}
+
+fn set_deinterlace_mode(element: &gst::Element, mode: DeinterlaceMode) {
+    let nick = match mode {
+        DeinterlaceMode::Auto => "auto",
+        DeinterlaceMode::Interlaced => "interlaced",
+        DeinterlaceMode::Disabled => "disabled",
+    };
+    trace!("set deinterlace mode of {} to {nick}", element.name());
+    element.set_property_from_str("mode", nick);
+}
+
+fn parse_audio_level(structure: &gst::StructureRef) -> Option<AudioLevel> {
+    // The level element reports the values of all channels in dB as a value array:
+    let channel_values = |name: &str| -> Option<Vec<f64>> {
+        let values = structure.get::<glib::ValueArray>(name).ok()?;
+        Some(values.iter().filter_map(|value| value.get::<f64>().ok()).collect())
+    };
+
+    Some(AudioLevel {
+        rms: channel_values("rms")?,
+        peak: channel_values("peak")?,
+        decay: channel_values("decay")?,
+    })
+}
+
+fn seek_key_units(play: &gst_play::Play) -> Result<(), glib::BoolError> {
+    let Some(position) = play.position() else {
+        return Ok(());
+    };
+    let rate = play.rate();
+    let (start, stop) = if rate < 0.0 {
+        (Some(gst::ClockTime::ZERO), Some(position))
+    } else {
+        (Some(position), gst::ClockTime::NONE)
+    };
+    let flags = gst::SeekFlags::FLUSH
+        | gst::SeekFlags::TRICKMODE
+        | gst::SeekFlags::TRICKMODE_KEY_UNITS
+        | gst::SeekFlags::TRICKMODE_NO_AUDIO;
+    trace!("seek key units from {start:?} to {stop:?} with rate {rate}");
+    play.pipeline()
+        .seek(rate, flags, gst::SeekType::Set, start, gst::SeekType::Set, stop)
+}
+
+/// Seeks into the loop region in segment mode, which also applies the key unit trick mode at speeds other than 1.0.
+fn seek_loop_region(
+    play: &gst_play::Play,
+    (start, end): (gst::ClockTime, gst::ClockTime),
+    position: Option<gst::ClockTime>,
+    trick_mode: TrickMode,
+    flush: bool,
+) -> Result<(), glib::BoolError> {
+    let rate = play.rate();
+    let boundary = if rate < 0.0 { end } else { start };
+    let position = position
+        .filter(|position| (start..end).contains(position))
+        .unwrap_or(boundary);
+    let (seek_start, seek_end) = if rate < 0.0 { (start, position) } else { (position, end) };
+    let mut flags = if trick_mode == TrickMode::KeyUnits && rate != 1.0 {
+        gst::SeekFlags::SEGMENT
+            | gst::SeekFlags::TRICKMODE
+            | gst::SeekFlags::TRICKMODE_KEY_UNITS
+            | gst::SeekFlags::TRICKMODE_NO_AUDIO
+    } else {
+        gst::SeekFlags::SEGMENT | gst::SeekFlags::ACCURATE
+    };
+    if flush {
+        flags |= gst::SeekFlags::FLUSH;
+    }
+    trace!("seek loop region from {seek_start} to {seek_end} with rate {rate}");
+    play.pipeline().seek(
+        rate,
+        flags,
+        gst::SeekType::Set,
+        seek_start,
+        gst::SeekType::Set,
+        seek_end,
+    )
 }
 
 impl PlayerData {
+    /// Resets everything, which belongs to the media of the current URI.
+    fn reset_media(&mut self) {
+        self.loop_region = LoopRegion::default();
+        self.rearm_loop = false;
+        self.rearm_trick_mode = false;
+        self.chapters.clear();
+        self.missing_plugins.clear();
+        self.errors.clear();
+        self.warnings.clear();
+    }
+
+    /// The filters, which are applied or waiting for the pipeline to stop.
+    fn staged_filters(&self) -> &Filters {
+        self.filter_restore
+            .as_ref()
+            .and_then(|restore| restore.filters.as_ref())
+            .unwrap_or(&self.filters)
+    }
+
+    /// Applies the filters to the pipeline, or keeps the previous filters if they cannot be applied.
+    fn replace_filters(&mut self, pipeline: &gst::Element, filters: Filters) -> Result<()> {
+        match filters.apply(pipeline) {
+            Ok(()) => {
+                self.filters = filters;
+                Ok(())
+            }
+            Err(error) => {
+                if let Err(error) = self.filters.apply(pipeline) {
+                    warn!("Cannot restore the previous filters: {error}");
+                }
+                Err(error)
+            }
+        }
+    }
+
+    /// Continues the filter restore at a state change of the player.
+    ///
+    /// Returns the restore to apply, when the pipeline stopped and the playback has to be restarted.
+    fn continue_filter_restore(&mut self, pipeline: &gst::Element, state: PlaybackState) -> Option<FilterRestore> {
+        let mut restore = self.filter_restore.take()?;
+        match restore.filters.take() {
+            Some(filters) if state == PlaybackState::Stopped => {
+                if let Err(error) = self.replace_filters(pipeline, filters) {
+                    self.send(PlayerComponentCommand::Warning(error.into()));
+                }
+                if restore.state == PlaybackState::Stopped {
+                    self.change_state(PlaybackState::Stopped);
+                    return None;
+                }
+                // Restarting the playback leaves the segment and trick mode like a seek of the player:
+                self.rearm_loop = restore.position.is_some() && self.loop_region.active().is_some();
+                self.rearm_trick_mode = restore.position.is_some() && self.trick_mode == TrickMode::KeyUnits;
+                self.filter_restore = Some(restore.clone());
+                Some(restore)
+            }
+            filters => {
+                restore.filters = filters;
+                if restore.filters.is_none() && state == restore.state {
+                    trace!("restored player state {state:?} after filter change");
+                    self.change_state(state);
+                } else {
+                    self.filter_restore = Some(restore);
+                }
+                None
+            }
+        }
+    }
+
     fn change_state(&mut self, new_state: PlaybackState) {
         let target_state = if let Some(current_state) = self.current_state {
             if current_state != new_state {
@@ -265,17 +1036,70 @@ impl PlayerData {
         let old_state = self.current_state;
         self.current_state = Some(new_state);
         trace!("player state changed from {old_state:?} to {new_state:?}");
+        self.complete_pending_operations(PendingEvent::StateChanged(new_state));
+        if new_state == PlaybackState::Stopped {
+            // Seeks and other state changes cannot finish anymore, but a new URI can still be loaded:
+            let (interrupted, pending): (Vec<_>, Vec<_>) = std::mem::take(&mut self.pending_operations)
+                .into_iter()
+                .partition(|operation| !matches!(operation.event, PendingEvent::MediaInfoUpdated(_)));
+            self.pending_operations = pending;
+            for operation in interrupted {
+                operation
+                    .sender
+                    .send(Err(anyhow::anyhow!("Player was stopped before the operation finished")))
+                    .unwrap_or_default();
+            }
+        }
         self.send(PlayerComponentCommand::StateChanged(old_state, new_state));
     }
 
+    fn add_pending_operation(&mut self, event: PendingEvent) -> oneshot::Receiver<Result<()>> {
+        let (sender, receiver) = oneshot::channel();
+        match event {
+            PendingEvent::StateChanged(state) if self.current_state == Some(state) => {
+                sender.send(Ok(())).unwrap_or_default();
+            }
+            // A player, which never started, is already stopped:
+            PendingEvent::StateChanged(PlaybackState::Stopped) if self.current_state.is_none() => {
+                sender.send(Ok(())).unwrap_or_default();
+            }
+            _ => self.pending_operations.push(PendingOperation { event, sender }),
+        }
+        receiver
+    }
+
+    fn complete_pending_operations(&mut self, event: PendingEvent) {
+        let (completed, pending): (Vec<_>, Vec<_>) = std::mem::take(&mut self.pending_operations)
+            .into_iter()
+            .partition(|operation| operation.event == event);
+        self.pending_operations = pending;
+        for operation in completed {
+            trace!("pending player operation {event:?} completed");
+            operation.sender.send(Ok(())).unwrap_or_default();
+        }
+    }
+
+    fn fail_pending_operations(&mut self, error: impl Fn() -> anyhow::Error) {
+        for operation in self.pending_operations.drain(..) {
+            operation.sender.send(Err(error())).unwrap_or_default();
+        }
+    }
+
     fn send(&self, cmd: PlayerComponentCommand) {
-        self.sender.send(cmd).unwrap_or_default();
+        self.event_sink.send(cmd);
     }
 }
 
 impl Player {
-    pub fn paintable(&self) -> gdk::Paintable {
-        self.gtk_sink.property::<gdk::Paintable>("paintable")
+    /// The paintable to display the video, which is only available for [`VideoSink::Gtk`].
+    pub fn paintable(&self) -> Option<gdk::Paintable> {
+        self.gtk_sink
+            .as_ref()
+            .map(|gtk_sink| gtk_sink.property::<gdk::Paintable>("paintable"))
+    }
+
+    pub fn video_sink(&self) -> &gst::Element {
+        &self.video_sink
     }
 
     pub fn update_render_rectangle(&self, src_rect: &gst_video::VideoRectangle, new_rect: gst_video::VideoRectangle) {
@@ -286,18 +1110,104 @@ impl Player {
 
     pub fn set_uri(&self, uri: &str) {
         debug!("player set uri {uri}");
+        {
+            let mut player_data = self.data.lock().unwrap();
+            player_data.reset_media();
+            if let Some(restore) = player_data.filter_restore.as_mut() {
+                restore.position = None;
+            }
+            player_data.current_uri = Some(uri.to_string());
+            player_data.gapless_uri = None;
+        }
         self.player.set_uri(Some(uri));
     }
 
+    /// The URI of the current media, which also reflects gapless switches.
+    ///
+    /// The URI is tracked by the player, because the underlying `gst_play::Play` does not know about the URIs
+    /// switched gaplessly, see [`Player::set_next_uri`].
+    pub fn uri(&self) -> Option<String> {
+        self.data.lock().unwrap().current_uri.clone()
+    }
+
+    /// Set the URI to continue with, when the current URI is about to finish.
+    ///
+    /// The switch is reported by [`PlayerComponentCommand::GaplessSwitched`]. Without a next URI the player reports
+    /// the end of stream as usual.
+    pub fn set_next_uri(&self, uri: Option<&str>) {
+        debug!("player set next uri {uri:?}");
+        self.data.lock().unwrap().next_uri = uri.map(ToString::to_string);
+    }
+
+    fn add_pending_operation(&self, event: PendingEvent) -> oneshot::Receiver<Result<()>> {
+        self.data.lock().unwrap().add_pending_operation(event)
+    }
+
+    /// Load a new URI and wait until its media info is known.
+    ///
+    /// The media info is only discovered once the playback is started or paused.
+    pub fn set_uri_async(&self, uri: &str) -> impl Future<Output = Result<()>> {
+        let receiver = self.add_pending_operation(PendingEvent::MediaInfoUpdated(uri.to_string()));
+        self.set_uri(uri);
+        wait_for_operation(receiver)
+    }
+
+    pub fn play_async(&self) -> impl Future<Output = Result<()>> {
+        let receiver = self.add_pending_operation(PendingEvent::StateChanged(PlaybackState::Playing));
+        self.play();
+        wait_for_operation(receiver)
+    }
+
+    pub fn pause_async(&self) -> impl Future<Output = Result<()>> {
+        let receiver = self.add_pending_operation(PendingEvent::StateChanged(PlaybackState::Paused));
+        self.pause();
+        wait_for_operation(receiver)
+    }
+
+    pub fn stop_async(&self) -> impl Future<Output = Result<()>> {
+        let receiver = self.add_pending_operation(PendingEvent::StateChanged(PlaybackState::Stopped));
+        self.stop();
+        wait_for_operation(receiver)
+    }
+
+    pub fn seek_async(&self, to: &f64) -> impl Future<Output = Result<()>> {
+        let receiver = self.add_pending_operation(PendingEvent::SeekDone);
+        self.seek(to);
+        wait_for_operation(receiver)
+    }
+
     pub fn play(&self) {
-        self.player.play();
+        if !self.redirect_filter_restore(PlaybackState::Playing) {
+            self.player.play();
+        }
     }
 
     pub fn pause(&self) {
-        self.player.pause();
+        if !self.redirect_filter_restore(PlaybackState::Paused) {
+            self.player.pause();
+        }
+    }
+
+    /// Lets a running filter restore end in the requested state.
+    ///
+    /// Returns `true` while the player is still stopping to replace the filters, which restarts the playback in the
+    /// requested state afterwards.
+    fn redirect_filter_restore(&self, state: PlaybackState) -> bool {
+        let mut player_data = self.data.lock().unwrap();
+        match player_data.filter_restore.as_mut() {
+            Some(restore) => {
+                trace!("continue filter restore in state {state:?}");
+                restore.state = state;
+                restore.filters.is_some()
+            }
+            None => false,
+        }
     }
 
     pub fn stop(&self) {
+        if self.redirect_filter_restore(PlaybackState::Stopped) {
+            return;
+        }
         let mut player_data = self.data.lock().unwrap();
         if let Some(current_state) = player_data.current_state {
             match current_state {
@@ -316,13 +1226,126 @@ impl Player {
                 }
             }
         }
+        // Errors, which were not forwarded until now, do not belong to the next playback:
+        player_data.errors.clear();
+        player_data.warnings.clear();
         drop(player_data);
         self.player.stop();
     }
 
     pub fn seek(&self, to: &f64) {
-        let to = gst::ClockTime::from_mseconds((to * 1000_f64) as u64);
-        self.player.seek(to);
+        self.request_seek_rearm();
+        self.player.seek(seconds_to_clock_time(*to));
+    }
+
+    /// The current position in seconds.
+    pub fn position(&self) -> Option<f64> {
+        self.player.position().map(clock_time_to_seconds)
+    }
+
+    /// The chapters of the current media, which are read from its table of contents.
+    pub fn chapters(&self) -> Vec<Chapter> {
+        self.data.lock().unwrap().chapters.clone()
+    }
+
+    /// Index of the chapter, which contains the position in seconds.
+    pub fn chapter_at(&self, position: f64) -> Option<usize> {
+        chapter::chapter_at(&self.data.lock().unwrap().chapters, position)
+    }
+
+    pub fn next_chapter(&self) -> Result<()> {
+        let position = self.position().unwrap_or_default();
+        let start =
+            chapter::next_chapter_start(&self.chapters(), position).with_context(|| "There is no next chapter")?;
+        debug!("seek to next chapter at {start}");
+        self.seek(&start);
+        Ok(())
+    }
+
+    /// Seeks to the start of the current chapter, or to the previous chapter if the current chapter just started.
+    pub fn previous_chapter(&self) -> Result<()> {
+        let position = self.position().unwrap_or_default();
+        let start =
+            chapter::previous_chapter_start(&self.chapters(), position).with_context(|| "The media has no chapters")?;
+        debug!("seek to previous chapter at {start}");
+        self.seek(&start);
+        Ok(())
+    }
+
+    /// Set the start of the loop region, the current position is used if `position` is `None`.
+    pub fn set_loop_start(&self, position: Option<f64>) -> Result<()> {
+        let position = position.map(seconds_to_clock_time).or_else(|| self.player.position());
+        let was_active = {
+            let mut player_data = self.data.lock().unwrap();
+            let was_active = player_data.loop_region.active().is_some();
+            player_data.loop_region.start = position;
+            if let (Some(start), Some(end)) = (player_data.loop_region.start, player_data.loop_region.end) {
+                if start >= end {
+                    player_data.loop_region.end = None;
+                }
+            }
+            was_active
+        };
+        self.apply_loop_region(was_active)
+    }
+
+    /// Set the end of the loop region, the current position is used if `position` is `None`.
+    pub fn set_loop_end(&self, position: Option<f64>) -> Result<()> {
+        let position = position.map(seconds_to_clock_time).or_else(|| self.player.position());
+        let was_active = {
+            let mut player_data = self.data.lock().unwrap();
+            let was_active = player_data.loop_region.active().is_some();
+            player_data.loop_region.end = position;
+            was_active
+        };
+        self.apply_loop_region(was_active)
+    }
+
+    pub fn clear_loop(&self) -> Result<()> {
+        let was_active = {
+            let mut player_data = self.data.lock().unwrap();
+            let was_active = player_data.loop_region.active().is_some();
+            player_data.loop_region = LoopRegion::default();
+            was_active
+        };
+        self.apply_loop_region(was_active)
+    }
+
+    /// The active loop region as start and end position in seconds.
+    pub fn loop_region(&self) -> Option<(f64, f64)> {
+        let player_data = self.data.lock().unwrap();
+        player_data
+            .loop_region
+            .active()
+            .map(|(start, end)| (clock_time_to_seconds(start), clock_time_to_seconds(end)))
+    }
+
+    fn apply_loop_region(&self, was_active: bool) -> Result<()> {
+        let (loop_region, trick_mode) = {
+            let player_data = self.data.lock().unwrap();
+            (player_data.loop_region.active(), player_data.trick_mode)
+        };
+        if let Some(loop_region) = loop_region {
+            debug!("player loop region {loop_region:?}");
+            if self.player.position().is_some() {
+                seek_loop_region(&self.player, loop_region, self.player.position(), trick_mode, true)
+                    .with_context(|| "Cannot seek to the loop region")?;
+            }
+        } else if was_active {
+            debug!("player leave loop region");
+            // A regular seek leaves the segment mode and continues the playback to the end of the stream:
+            if let Some(position) = self.player.position() {
+                self.request_seek_rearm();
+                self.player.seek(position);
+            }
+        }
+        Ok(())
+    }
+
+    fn request_seek_rearm(&self) {
+        let mut player_data = self.data.lock().unwrap();
+        player_data.rearm_loop = player_data.loop_region.active().is_some();
+        player_data.rearm_trick_mode = player_data.trick_mode == TrickMode::KeyUnits;
     }
 
     pub fn set_volume(&self, vol: f64) {
@@ -343,12 +1366,326 @@ impl Player {
         Ok(())
     }
 
+    pub fn set_video_track(&self, track: Track) -> Result<()> {
+        match track {
+            Track::Enable => self.player.set_video_track_enabled(true),
+            Track::Disable => self.player.set_video_track_enabled(false),
+            Track::Stream(index) => {
+                self.player.set_video_track_enabled(true);
+                self.player
+                    .set_video_track(index)
+                    .with_context(|| "Cannot set video stream")?
+            }
+        }
+        Ok(())
+    }
+
+    /// Show or hide a single video stream inside of the compositor configured by [`PlayerBuilder::compositor`].
+    pub fn set_video_stream_visible(&self, index: i32, visible: bool) -> Result<()> {
+        let compositor = self
+            .compositor
+            .as_ref()
+            .with_context(|| "Cannot change the video stream visibility without a compositor")?;
+        let pad = self.compositor_pad(compositor, index)?;
+        if pad.find_property("alpha").is_none() {
+            anyhow::bail!(
+                "Compositor pad '{}' does not support changing the visibility",
+                pad.name()
+            );
+        }
+        debug!("set visibility of video stream {index} to {visible}");
+        pad.set_property("alpha", if visible { 1.0_f64 } else { 0.0_f64 });
+        Ok(())
+    }
+
+    /// The sink pad of the compositor, which receives the video stream.
+    ///
+    /// The pads are requested in the order the streams are linked, so their names do not match the stream index.
+    /// The playbin knows which pad it linked to each stream.
+    fn compositor_pad(&self, compositor: &gst::Element, index: i32) -> Result<gst::Pad> {
+        let pipeline = self.player.pipeline();
+        if glib::subclass::SignalId::lookup("get-video-pad", pipeline.type_()).is_none() {
+            anyhow::bail!("The playbin cannot map video stream {index} to a compositor pad");
+        }
+        let pad = pipeline
+            .emit_by_name::<Option<gst::Pad>>("get-video-pad", &[&index])
+            .with_context(|| format!("There is no compositor pad for video stream {index}"))?;
+        if pad.parent_element().as_ref() != Some(compositor) {
+            anyhow::bail!("Video stream {index} is not linked to the compositor");
+        }
+        Ok(pad)
+    }
+
+    pub fn set_subtitle_track(&self, track: Track) -> Result<()> {
+        match track {
+            Track::Enable => self.player.set_subtitle_track_enabled(true),
+            Track::Disable => self.player.set_subtitle_track_enabled(false),
+            Track::Stream(index) => {
+                self.player.set_subtitle_track_enabled(true);
+                self.player
+                    .set_subtitle_track(index)
+                    .with_context(|| "Cannot set subtitle stream")?
+            }
+        }
+        Ok(())
+    }
+
+    pub fn set_subtitle_uri(&self, uri: Option<&str>) {
+        debug!("player set subtitle uri {uri:?}");
+        self.player.set_subtitle_uri(uri);
+        if uri.is_some() {
+            self.player.set_subtitle_track_enabled(true);
+        }
+    }
+
+    /// The value of a color balance channel in the range from 0.0 to 1.0, where 0.5 is the neutral value.
+    pub fn color_balance(&self, channel: ColorBalanceChannel) -> f64 {
+        self.player.color_balance(channel.into())
+    }
+
+    pub fn set_color_balance(&self, channel: ColorBalanceChannel, value: f64) -> Result<()> {
+        if !self.player.has_color_balance() {
+            anyhow::bail!("The player does not support color balance");
+        }
+        debug!("player set color balance {channel:?} to {value}");
+        self.player.set_color_balance(channel.into(), value.clamp(0.0, 1.0));
+        Ok(())
+    }
+
+    /// The gamma correction, which is only available if enabled by [`PlayerBuilder::gamma`].
+    pub fn gamma(&self) -> Option<f64> {
+        self.gamma.as_ref().map(|gamma| gamma.property::<f64>("gamma"))
+    }
+
+    pub fn set_gamma(&self, value: f64) -> Result<()> {
+        let gamma = self
+            .gamma
+            .as_ref()
+            .with_context(|| "Gamma correction is not enabled for the player")?;
+        debug!("player set gamma to {value}");
+        gamma.set_property("gamma", value.clamp(GAMMA_MIN, GAMMA_MAX));
+        Ok(())
+    }
+
+    pub fn deinterlace_mode(&self) -> DeinterlaceMode {
+        self.data.lock().unwrap().deinterlace_mode
+    }
+
+    pub fn set_deinterlace_mode(&self, mode: DeinterlaceMode) {
+        debug!("player set deinterlace mode to {mode:?}");
+        self.data.lock().unwrap().deinterlace_mode = mode;
+        if let Ok(bin) = self.player.pipeline().downcast::<gst::Bin>() {
+            bin.iterate_recurse()
+                .into_iter()
+                .filter_map(Result::ok)
+                .filter(is_deinterlacer)
+                .for_each(|element| set_deinterlace_mode(&element, mode));
+        }
+    }
+
+    /// Replaces the video filter.
+    ///
+    /// The filters cannot be replaced in a running pipeline, so the player is stopped and the playback state and
+    /// position is restored with the new filter. The restart is not reported as state change. If the filter cannot
+    /// be applied to a running pipeline, the previous filters are kept and a warning is reported.
+    pub fn set_video_filter(&self, filter: Option<FilterSpec>) -> Result<()> {
+        let filter = filter.as_ref().map(FilterSpec::build).transpose()?;
+        self.reconfigure_filters(|filters| filters.video = filter)
+    }
+
+    /// Replaces the audio filter, see [`Player::set_video_filter`].
+    pub fn set_audio_filter(&self, filter: Option<FilterSpec>) -> Result<()> {
+        let filter = filter.as_ref().map(FilterSpec::build).transpose()?;
+        self.reconfigure_filters(|filters| filters.audio = filter)
+    }
+
+    pub fn preserve_pitch(&self) -> bool {
+        self.data.lock().unwrap().staged_filters().scaletempo.is_some()
+    }
+
+    /// Keeps the audio pitch constant at speeds other than 1.0 with the `scaletempo` element.
+    ///
+    /// Like [`Player::set_audio_filter`] this restarts a running playback at the current position, which is not
+    /// reported as state change.
+    pub fn set_preserve_pitch(&self, preserve_pitch: bool) -> Result<()> {
+        if preserve_pitch == self.preserve_pitch() {
+            return Ok(());
+        }
+        let scaletempo = if preserve_pitch {
+            Some(build_scaletempo()?)
+        } else {
+            None
+        };
+        debug!("player set preserve pitch to {preserve_pitch}");
+        self.reconfigure_filters(|filters| filters.scaletempo = scaletempo)
+    }
+
+    /// Changes the filters, which are only committed once they were applied to the pipeline.
+    fn reconfigure_filters(&self, change: impl FnOnce(&mut Filters)) -> Result<()> {
+        let mut player_data = self.data.lock().unwrap();
+        let mut filters = player_data.staged_filters().clone();
+        change(&mut filters);
+        if let Some(restore) = player_data
+            .filter_restore
+            .as_mut()
+            .filter(|restore| restore.filters.is_some())
+        {
+            trace!("replace filters, which wait for the player to stop");
+            restore.filters = Some(filters);
+            return Ok(());
+        }
+        match player_data.current_state {
+            Some(state @ (PlaybackState::Playing | PlaybackState::Paused | PlaybackState::Buffering)) => {
+                trace!("stop player to replace filters");
+                player_data.filter_restore = Some(FilterRestore {
+                    state: match state {
+                        PlaybackState::Buffering => PlaybackState::Playing,
+                        state => state,
+                    },
+                    position: self.player.position(),
+                    filters: Some(filters),
+                });
+                drop(player_data);
+                self.player.stop();
+                Ok(())
+            }
+            _ => player_data.replace_filters(&self.player.pipeline(), filters),
+        }
+    }
+
     pub fn speed(&self) -> f64 {
         self.player.rate()
     }
 
-    pub fn set_speed(&self, speed: f64) {
+    /// Current statistics of the video output, e.g. to diagnose stuttering playback.
+    pub fn statistics(&self) -> Statistics {
+        let sink = self.gtk_sink.as_ref().unwrap_or(&self.video_sink);
+        let stats = sink
+            .find_property("stats")
+            .map(|_| sink.property::<gst::Structure>("stats"));
+        let stats_value = |name: &str| {
+            stats
+                .as_ref()
+                .and_then(|stats| stats.get::<u64>(name).ok())
+                .unwrap_or_default()
+        };
+        let rendered_frames = stats_value("rendered");
+        let dropped_frames = stats_value("dropped");
+
+        let now = Instant::now();
+        let fps = {
+            let mut player_data = self.data.lock().unwrap();
+            let fps = player_data
+                .statistics_sample
+                .filter(|(rendered, _)| *rendered <= rendered_frames)
+                .map(|(rendered, time)| (rendered_frames - rendered) as f64 / now.duration_since(time).as_secs_f64())
+                .filter(|fps| fps.is_finite());
+            player_data.statistics_sample = Some((rendered_frames, now));
+            fps
+        };
+
+        let mut query = gst::query::Buffering::new(gst::Format::Time);
+        let bitrate = if self.player.pipeline().query(&mut query) {
+            let (_, avg_in, _, _) = query.stats();
+            u64::try_from(avg_in)
+                .ok()
+                .filter(|bytes| *bytes > 0)
+                .map(|bytes| bytes * 8)
+        } else {
+            None
+        };
+
+        Statistics {
+            rendered_frames,
+            dropped_frames,
+            fps,
+            video_decoder: self.video_decoder(),
+            bitrate,
+            video_caps: self
+                .video_sink
+                .static_pad("sink")
+                .and_then(|pad| pad.current_caps())
+                .map(|caps| caps.to_string()),
+        }
+    }
+
+    fn video_decoder(&self) -> Option<String> {
+        self.player
+            .pipeline()
+            .downcast::<gst::Bin>()
+            .ok()?
+            .iterate_recurse()
+            .into_iter()
+            .filter_map(Result::ok)
+            .filter_map(|element| element.factory())
+            .find(|factory| {
+                factory
+                    .metadata(gst::ELEMENT_METADATA_KLASS)
+                    .is_some_and(|klass| klass.contains("Decoder") && klass.contains("Video"))
+            })
+            .map(|factory| factory.name().to_string())
+    }
+
+    /// The time ranges in seconds, which are already downloaded or buffered by the pipeline.
+    ///
+    /// The ranges are empty if the media is not buffered, e.g. for local files.
+    pub fn buffered_ranges(&self) -> Vec<(f64, f64)> {
+        let Some(duration) = self.player.duration() else {
+            return Vec::new();
+        };
+        let mut query = gst::query::Buffering::new(gst::Format::Percent);
+        if !self.player.pipeline().query(&mut query) {
+            return Vec::new();
+        }
+        let to_seconds = |value: gst::GenericFormattedValue| {
+            let ratio = value.value().clamp(0, gst::ffi::GST_FORMAT_PERCENT_MAX) as f64
+                / gst::ffi::GST_FORMAT_PERCENT_MAX as f64;
+            clock_time_to_seconds(duration) * ratio
+        };
+        query
+            .ranges()
+            .into_iter()
+            .map(|(start, stop)| (to_seconds(start), to_seconds(stop)))
+            .filter(|(start, stop)| start < stop)
+            .collect()
+    }
+
+    /// Sets the playback speed in the range from [`SPEED_MIN`] to [`SPEED_MAX`].
+    ///
+    /// Negative speeds play in reverse, which requires [`Player::supports_reverse_playback`].
+    pub fn set_speed(&self, speed: f64) -> Result<()> {
+        if speed == 0.0 || !(SPEED_MIN..=SPEED_MAX).contains(&speed) {
+            anyhow::bail!("Playback speed {speed} is out of the supported range {SPEED_MIN} to {SPEED_MAX}");
+        }
+        if speed < 0.0 && !self.supports_reverse_playback() {
+            anyhow::bail!("The current media does not support reverse playback");
+        }
+        self.request_seek_rearm();
         self.player.set_rate(speed);
+        Ok(())
+    }
+
+    /// Whether the current media can be played with a negative speed, which requires a seekable media.
+    pub fn supports_reverse_playback(&self) -> bool {
+        self.player
+            .media_info()
+            .is_some_and(|info| info.is_seekable() && !info.is_live())
+    }
+
+    pub fn trick_mode(&self) -> TrickMode {
+        self.data.lock().unwrap().trick_mode
+    }
+
+    pub fn set_trick_mode(&self, mode: TrickMode) {
+        debug!("player set trick mode to {mode:?}");
+        self.data.lock().unwrap().trick_mode = mode;
+        if self.player.rate() != 1.0 {
+            if let Some(position) = self.player.position() {
+                // Seek to the current position to apply the trick mode for the current speed:
+                self.request_seek_rearm();
+                self.player.seek(position);
+            }
+        }
     }
 
     pub fn next_frame(&self) {
@@ -358,6 +1695,83 @@ impl Player {
             .send_event(Step::new(Buffers::from_u64(1), 1., true, false));
     }
 
+    /// Step back to the previous frame by an accurate seek of one frame duration before the current position.
+    ///
+    /// An active loop region is kept, the step does not leave the region.
+    pub fn previous_frame(&self) -> Result<()> {
+        let frame_duration = self
+            .frame_duration()
+            .with_context(|| "Cannot step to the previous frame without a known framerate")?;
+        let position = self
+            .player
+            .position()
+            .with_context(|| "Cannot step to the previous frame without a known position")?;
+        let target = position.saturating_sub(frame_duration);
+        let loop_region = self.data.lock().unwrap().loop_region.active();
+        if let Some(loop_region) = loop_region {
+            // Stay in the segment mode of the loop region, the step is limited to the start of the region:
+            trace!("step to previous frame at {target} in loop region {loop_region:?}");
+            return seek_loop_region(&self.player, loop_region, Some(target), TrickMode::Accurate, true)
+                .with_context(|| "Cannot seek to the previous frame");
+        }
+        // A simple seek resets the speed to 1.0, so keep the current rate and the direction of the segment:
+        let rate = self.player.rate();
+        let (start, stop) = if rate < 0.0 {
+            (Some(gst::ClockTime::ZERO), Some(target))
+        } else {
+            (Some(target), gst::ClockTime::NONE)
+        };
+        trace!("step to previous frame at {target} with rate {rate}");
+        self.player
+            .pipeline()
+            .seek(
+                rate,
+                gst::SeekFlags::FLUSH | gst::SeekFlags::ACCURATE,
+                gst::SeekType::Set,
+                start,
+                gst::SeekType::Set,
+                stop,
+            )
+            .with_context(|| "Cannot seek to the previous frame")?;
+        Ok(())
+    }
+
+    /// Duration of a single frame of the current video track.
+    pub fn frame_duration(&self) -> Option<gst::ClockTime> {
+        let framerate = self.player.current_video_track()?.framerate();
+        if framerate.numer() <= 0 || framerate.denom() <= 0 {
+            return None;
+        }
+        Some(gst::ClockTime::from_nseconds(
+            gst::ClockTime::SECOND.nseconds() * framerate.denom() as u64 / framerate.numer() as u64,
+        ))
+    }
+
+    /// Take a snapshot of the currently displayed video frame.
+    ///
+    /// The returned sample contains the encoded image. If `size` is set, the frame is scaled to the given
+    /// width and height.
+    pub fn snapshot(&self, format: SnapshotFormat, size: Option<(i32, i32)>) -> Result<gst::Sample> {
+        self.snapshot_saver().snapshot(format, size)
+    }
+
+    pub fn snapshot_texture(&self, size: Option<(i32, i32)>) -> Result<gdk::Texture> {
+        let sample = self.snapshot(SnapshotFormat::Png, size)?;
+        let buffer = sample.buffer().with_context(|| "Snapshot does not contain any data")?;
+        let map = buffer.map_readable()?;
+        gdk::Texture::from_bytes(&gdk::glib::Bytes::from(map.as_slice()))
+            .with_context(|| "Cannot create texture from snapshot")
+    }
+
+    pub fn save_snapshot(&self, path: &Path, format: SnapshotFormat, size: Option<(i32, i32)>) -> Result<()> {
+        self.snapshot_saver().save(path, format, size)
+    }
+
+    /// Handle to take snapshots from another thread, because encoding and writing them blocks.
+    pub fn snapshot_saver(&self) -> SnapshotSaver {
+        SnapshotSaver(self.player.clone())
+    }
+
     pub fn set_audio_video_offset(&self, offset: i64) {
         self.player.set_audio_video_offset(offset);
     }
@@ -373,3 +1787,35 @@ impl Player {
         }
     }
 }
+
+/// Takes snapshots of the video frame currently displayed by a [`Player`], see [`Player::snapshot_saver`].
+#[derive(Debug, Clone)]
+pub struct SnapshotSaver(gst_play::Play);
+
+impl SnapshotSaver {
+    /// Take a snapshot of the currently displayed video frame, see [`Player::snapshot`].
+    pub fn snapshot(&self, format: SnapshotFormat, size: Option<(i32, i32)>) -> Result<gst::Sample> {
+        let config = size.map(|(width, height)| {
+            gst::Structure::builder("config")
+                .field("width", width)
+                .field("height", height)
+                .build()
+        });
+        let format = match format {
+            SnapshotFormat::Png => gst_play::PlaySnapshotFormat::Png,
+            SnapshotFormat::Jpeg => gst_play::PlaySnapshotFormat::Jpg,
+        };
+        self.0
+            .video_snapshot(format, config.as_deref())
+            .with_context(|| "Cannot take a snapshot of the current video frame")
+    }
+
+    pub fn save(&self, path: &Path, format: SnapshotFormat, size: Option<(i32, i32)>) -> Result<()> {
+        debug!("save snapshot as {format:?} to {path:?}");
+        let sample = self.snapshot(format, size)?;
+        let buffer = sample.buffer().with_context(|| "Snapshot does not contain any data")?;
+        let map = buffer.map_readable()?;
+        std::fs::write(path, map.as_slice()).with_context(|| format!("Cannot write snapshot to {path:?}"))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,338 @@
+use anyhow::{Context, Result};
+use log::*;
+use mxl_relm4_components::relm4::gtk::{
+    gio::{prelude::FileExt, File},
+    glib,
+};
+use quick_xml::{events::Event, Reader};
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    path::{Path, PathBuf},
+};
+
+/// File formats of playlists, which can be loaded and saved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlaylistFormat {
+    /// M3U and M3U8 including the extended `#EXTINF` information.
+    M3u,
+    Xspf,
+    Pls,
+}
+
+impl PlaylistFormat {
+    /// Detects the format by the file extension of the path.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_lowercase();
+        match extension.as_str() {
+            "m3u" | "m3u8" => Some(Self::M3u),
+            "xspf" => Some(Self::Xspf),
+            "pls" => Some(Self::Pls),
+            _ => None,
+        }
+    }
+}
+
+/// Location of a playlist entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlaylistLocation {
+    /// Path of a local file.
+    Path(PathBuf),
+    /// URI of a remote media, e.g. `https://example.com/stream.m3u8`.
+    Uri(String),
+}
+
+impl PlaylistLocation {
+    /// Creates the location of a URI, `file` URIs are converted into local paths.
+    pub fn from_uri(uri: &str) -> Self {
+        if glib::Uri::peek_scheme(uri).is_some_and(|scheme| scheme.as_str() == "file") {
+            if let Some(path) = File::for_uri(uri).path() {
+                return Self::Path(path);
+            }
+        }
+        Self::Uri(uri.to_string())
+    }
+}
+
+impl From<PathBuf> for PlaylistLocation {
+    fn from(path: PathBuf) -> Self {
+        Self::Path(path)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlaylistFileEntry {
+    pub location: PlaylistLocation,
+    pub title: Option<String>,
+    /// Duration in seconds.
+    pub duration: Option<f64>,
+}
+
+impl PlaylistFileEntry {
+    pub fn new(location: impl Into<PlaylistLocation>) -> Self {
+        Self {
+            location: location.into(),
+            title: None,
+            duration: None,
+        }
+    }
+}
+
+/// Entry, which is collected from several lines or elements of a playlist file.
+#[derive(Default)]
+struct PartialEntry {
+    location: Option<PlaylistLocation>,
+    title: Option<String>,
+    duration: Option<f64>,
+}
+
+impl PartialEntry {
+    /// The entry is skipped, if its location is missing.
+    fn into_entry(self) -> Option<PlaylistFileEntry> {
+        Some(PlaylistFileEntry {
+            location: self.location?,
+            title: self.title,
+            duration: self.duration,
+        })
+    }
+}
+
+pub fn is_playlist_file(path: &Path) -> bool {
+    path.is_file() && PlaylistFormat::from_path(path).is_some()
+}
+
+/// Load the entries of a playlist file, relative locations are resolved against the directory of the playlist.
+pub fn load(path: &Path) -> Result<Vec<PlaylistFileEntry>> {
+    let format = PlaylistFormat::from_path(path)
+        .with_context(|| format!("Unknown playlist format of file {}", path.display()))?;
+    let content = std::fs::read(path).with_context(|| format!("Cannot read playlist file {}", path.display()))?;
+    let base_dir = path.parent().unwrap_or(Path::new(""));
+    let entries = parse(format, &String::from_utf8_lossy(&content), base_dir)
+        .with_context(|| format!("Cannot parse playlist file {}", path.display()))?;
+    debug!("loaded {} entries from playlist file {}", entries.len(), path.display());
+    Ok(entries)
+}
+
+/// Save the entries to a playlist file, locations inside the directory of the playlist are stored relative to it.
+pub fn save(path: &Path, entries: &[PlaylistFileEntry]) -> Result<()> {
+    let format = PlaylistFormat::from_path(path)
+        .with_context(|| format!("Unknown playlist format of file {}", path.display()))?;
+    let base_dir = path.parent().unwrap_or(Path::new(""));
+    std::fs::write(path, write(format, entries, base_dir))
+        .with_context(|| format!("Cannot write playlist file {}", path.display()))?;
+    debug!("saved {} entries to playlist file {}", entries.len(), path.display());
+    Ok(())
+}
+
+pub fn parse(format: PlaylistFormat, content: &str, base_dir: &Path) -> Result<Vec<PlaylistFileEntry>> {
+    match format {
+        PlaylistFormat::M3u => Ok(parse_m3u(content, base_dir)),
+        PlaylistFormat::Xspf => parse_xspf(content, base_dir),
+        PlaylistFormat::Pls => Ok(parse_pls(content, base_dir)),
+    }
+}
+
+pub fn write(format: PlaylistFormat, entries: &[PlaylistFileEntry], base_dir: &Path) -> String {
+    match format {
+        PlaylistFormat::M3u => write_m3u(entries, base_dir),
+        PlaylistFormat::Xspf => write_xspf(entries, base_dir),
+        PlaylistFormat::Pls => write_pls(entries, base_dir),
+    }
+}
+
+fn parse_m3u(content: &str, base_dir: &Path) -> Vec<PlaylistFileEntry> {
+    let mut entries = Vec::new();
+    let mut info = None;
+    for line in content.lines().map(|line| line.trim_start_matches('\u{feff}').trim()) {
+        if let Some(extinf) = line.strip_prefix("#EXTINF:") {
+            // Format: #EXTINF:<duration> [<attributes>],<title>
+            let (duration, title) = extinf.split_once(',').unwrap_or((extinf, ""));
+            let duration = duration
+                .split_whitespace()
+                .next()
+                .and_then(|duration| duration.parse::<f64>().ok())
+                .filter(|duration| *duration >= 0_f64);
+            let title = Some(title.trim().to_string()).filter(|title| !title.is_empty());
+            info = Some((duration, title));
+        } else if !line.is_empty() && !line.starts_with('#') {
+            let (duration, title) = info.take().unwrap_or_default();
+            entries.push(PlaylistFileEntry {
+                location: resolve_location(line, base_dir, false),
+                title,
+                duration,
+            });
+        }
+    }
+    entries
+}
+
+fn write_m3u(entries: &[PlaylistFileEntry], base_dir: &Path) -> String {
+    let mut content = "#EXTM3U\n".to_string();
+    for entry in entries {
+        if entry.title.is_some() || entry.duration.is_some() {
+            let duration = entry.duration.map_or(-1, |duration| duration.round() as i64);
+            writeln!(
+                content,
+                "#EXTINF:{duration},{}",
+                entry.title.as_deref().unwrap_or_default()
+            )
+            .unwrap();
+        }
+        writeln!(content, "{}", location_to_string(&entry.location, base_dir, false)).unwrap();
+    }
+    content
+}
+
+fn parse_pls(content: &str, base_dir: &Path) -> Vec<PlaylistFileEntry> {
+    let mut entries = BTreeMap::<usize, PartialEntry>::new();
+    for line in content.lines().map(str::trim) {
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let (key, value) = (key.trim().to_lowercase(), value.trim());
+        let split = key.find(|c: char| c.is_ascii_digit()).unwrap_or(key.len());
+        let (name, number) = key.split_at(split);
+        let Ok(number) = number.parse::<usize>() else {
+            continue;
+        };
+        let entry = entries.entry(number).or_default();
+        match name {
+            "file" => entry.location = Some(resolve_location(value, base_dir, false)),
+            "title" => entry.title = Some(value.to_string()).filter(|title| !title.is_empty()),
+            "length" => entry.duration = value.parse::<f64>().ok().filter(|duration| *duration >= 0_f64),
+            _ => (),
+        }
+    }
+    entries.into_values().filter_map(PartialEntry::into_entry).collect()
+}
+
+fn write_pls(entries: &[PlaylistFileEntry], base_dir: &Path) -> String {
+    let mut content = "[playlist]\n".to_string();
+    for (index, entry) in entries.iter().enumerate() {
+        let number = index + 1;
+        writeln!(
+            content,
+            "File{number}={}",
+            location_to_string(&entry.location, base_dir, false)
+        )
+        .unwrap();
+        if let Some(title) = &entry.title {
+            writeln!(content, "Title{number}={title}").unwrap();
+        }
+        let duration = entry.duration.map_or(-1, |duration| duration.round() as i64);
+        writeln!(content, "Length{number}={duration}").unwrap();
+    }
+    writeln!(content, "NumberOfEntries={}", entries.len()).unwrap();
+    writeln!(content, "Version=2").unwrap();
+    content
+}
+
+fn parse_xspf(content: &str, base_dir: &Path) -> Result<Vec<PlaylistFileEntry>> {
+    let mut entries = Vec::new();
+    let mut reader = Reader::from_str(content);
+    reader.config_mut().trim_text(true);
+
+    let mut track: Option<PartialEntry> = None;
+    let mut element = Vec::new();
+    loop {
+        match reader.read_event()? {
+            Event::Start(start) => {
+                element = start.local_name().as_ref().to_vec();
+                if element == b"track" {
+                    track = Some(PartialEntry::default());
+                }
+            }
+            Event::Text(text) => {
+                if let Some(track) = track.as_mut() {
+                    let text = text.unescape()?;
+                    match element.as_slice() {
+                        // Only the first location of a track is used:
+                        b"location" if track.location.is_none() => {
+                            track.location = Some(resolve_location(&text, base_dir, true))
+                        }
+                        b"title" => track.title = Some(text.to_string()),
+                        // The duration is given in milliseconds:
+                        b"duration" => track.duration = text.parse::<f64>().ok().map(|duration| duration / 1000_f64),
+                        _ => (),
+                    }
+                }
+            }
+            Event::End(end) => {
+                element.clear();
+                if end.local_name().as_ref() == b"track" {
+                    if let Some(track) = track.take().and_then(PartialEntry::into_entry) {
+                        entries.push(track);
+                    }
+                }
+            }
+            Event::Eof => break,
+            _ => (),
+        }
+    }
+    Ok(entries)
+}
+
+fn write_xspf(entries: &[PlaylistFileEntry], base_dir: &Path) -> String {
+    let escape = |text: &str| quick_xml::escape::escape(text).to_string();
+    let mut content = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n".to_string();
+    content.push_str("<playlist version=\"1\" xmlns=\"http://xspf.org/ns/0/\">\n");
+    content.push_str("  <trackList>\n");
+    for entry in entries {
+        content.push_str("    <track>\n");
+        let location = location_to_string(&entry.location, base_dir, true);
+        writeln!(content, "      <location>{}</location>", escape(&location)).unwrap();
+        if let Some(title) = &entry.title {
+            writeln!(content, "      <title>{}</title>", escape(title)).unwrap();
+        }
+        if let Some(duration) = entry.duration {
+            writeln!(
+                content,
+                "      <duration>{}</duration>",
+                (duration * 1000_f64).round() as u64
+            )
+            .unwrap();
+        }
+        content.push_str("    </track>\n");
+    }
+    content.push_str("  </trackList>\n");
+    content.push_str("</playlist>\n");
+    content
+}
+
+/// Converts a location of a playlist file into a path, remote URIs are kept as they are.
+fn resolve_location(location: &str, base_dir: &Path, escaped: bool) -> PlaylistLocation {
+    match glib::Uri::peek_scheme(location) {
+        // A single letter is the drive of a windows path:
+        Some(scheme) if scheme.len() > 1 => PlaylistLocation::from_uri(location),
+        _ => {
+            let location = if escaped {
+                glib::Uri::unescape_string(location, None)
+                    .map(|location| location.to_string())
+                    .unwrap_or_else(|| location.to_string())
+            } else {
+                location.to_string()
+            };
+            PlaylistLocation::Path(base_dir.join(location))
+        }
+    }
+}
+
+/// Converts a location into a location of a playlist file, paths are relative to the playlist, if possible.
+fn location_to_string(location: &PlaylistLocation, base_dir: &Path, escaped: bool) -> String {
+    let location = match location {
+        PlaylistLocation::Path(path) => path,
+        PlaylistLocation::Uri(uri) => return uri.clone(),
+    };
+    match location
+        .strip_prefix(base_dir)
+        .ok()
+        .filter(|_| !base_dir.as_os_str().is_empty())
+    {
+        Some(relative) if escaped => {
+            glib::Uri::escape_string(&relative.to_string_lossy(), Some("/"), false).to_string()
+        }
+        Some(relative) => relative.to_string_lossy().to_string(),
+        None if escaped => File::for_path(location).uri().to_string(),
+        None => location.to_string_lossy().to_string(),
+    }
+}
@@ -0,0 +1,7 @@
+use crate::ui::player::messages::AudioLevel;
+
+#[derive(Debug)]
+pub enum AudioLevelMeterComponentInput {
+    SetLevel(AudioLevel),
+    Reset,
+}
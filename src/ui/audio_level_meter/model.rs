@@ -0,0 +1,12 @@
+use mxl_relm4_components::relm4::gtk;
+
+#[derive(Debug)]
+pub struct AudioLevelMeterComponentInit {
+    pub orientation: gtk::Orientation,
+}
+
+#[derive(Debug)]
+pub struct AudioLevelMeterComponentModel {
+    pub(super) orientation: gtk::Orientation,
+    pub(super) bars: Vec<gtk::LevelBar>,
+}
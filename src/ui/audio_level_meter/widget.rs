@@ -0,0 +1,105 @@
+use crate::localization::helper::fl;
+use crate::ui::audio_level_meter::messages::AudioLevelMeterComponentInput;
+use crate::ui::audio_level_meter::model::{AudioLevelMeterComponentInit, AudioLevelMeterComponentModel};
+use crate::ui::player::messages::AudioLevel;
+use mxl_relm4_components::relm4::{self, gtk::prelude::*, prelude::*};
+
+/// Lowest level in dB, which is displayed as an empty bar.
+const LEVEL_MIN_DB: f64 = -60_f64;
+
+#[relm4::component(pub)]
+impl Component for AudioLevelMeterComponentModel {
+    type Init = AudioLevelMeterComponentInit;
+    type Input = AudioLevelMeterComponentInput;
+    type Output = ();
+    type CommandOutput = ();
+
+    view! {
+        gtk::Box {
+            // The bars of the channels are arranged side by side:
+            set_orientation: match model.orientation {
+                gtk::Orientation::Vertical => gtk::Orientation::Horizontal,
+                _ => gtk::Orientation::Vertical,
+            },
+            set_spacing: 2,
+        }
+    }
+
+    // Initialize the component.
+    fn init(init: Self::Init, root: Self::Root, _sender: ComponentSender<Self>) -> ComponentParts<Self> {
+        let model = AudioLevelMeterComponentModel {
+            orientation: init.orientation,
+            bars: Vec::new(),
+        };
+
+        let widgets = view_output!();
+
+        ComponentParts { model, widgets }
+    }
+
+    fn update_with_view(
+        &mut self,
+        widgets: &mut Self::Widgets,
+        msg: Self::Input,
+        sender: ComponentSender<Self>,
+        root: &Self::Root,
+    ) {
+        match msg {
+            AudioLevelMeterComponentInput::SetLevel(level) => {
+                if self.bars.len() != level.rms.len() {
+                    self.create_bars(root, level.rms.len());
+                }
+                self.set_level(&level);
+            }
+            AudioLevelMeterComponentInput::Reset => {
+                for bar in &self.bars {
+                    bar.set_value(0_f64);
+                    bar.set_tooltip_text(None);
+                }
+            }
+        }
+        self.update_view(widgets, sender)
+    }
+}
+
+impl AudioLevelMeterComponentModel {
+    fn create_bars(&mut self, root: &gtk::Box, channels: usize) {
+        for bar in self.bars.drain(..) {
+            root.remove(&bar);
+        }
+        for _ in 0..channels {
+            let bar = gtk::LevelBar::builder()
+                .orientation(self.orientation)
+                .inverted(self.orientation == gtk::Orientation::Vertical)
+                .min_value(0_f64)
+                .max_value(1_f64)
+                .hexpand(self.orientation == gtk::Orientation::Horizontal)
+                .vexpand(self.orientation == gtk::Orientation::Vertical)
+                .build();
+            root.append(&bar);
+            self.bars.push(bar);
+        }
+    }
+
+    fn set_level(&self, level: &AudioLevel) {
+        for (index, bar) in self.bars.iter().enumerate() {
+            let rms = level.rms.get(index).copied().unwrap_or(f64::NEG_INFINITY);
+            let peak = level.peak.get(index).copied().unwrap_or(f64::NEG_INFINITY);
+            bar.set_value(db_to_level(rms));
+            bar.set_tooltip_text(Some(&fl!(
+                "audio-level",
+                channel = index + 1,
+                rms = format!("{rms:.1}"),
+                peak = format!("{peak:.1}")
+            )));
+        }
+    }
+}
+
+fn db_to_level(db: f64) -> f64 {
+    if db.is_finite() {
+        ((db - LEVEL_MIN_DB) / -LEVEL_MIN_DB).clamp(0_f64, 1_f64)
+    } else {
+        0_f64
+    }
+}
@@ -0,0 +1,9 @@
+use crate::media_info::MediaInfo;
+
+#[derive(Debug)]
+pub enum MediaInfoComponentInput {
+    /// Show the media info, e.g. of `PlayerComponentOutput::MediaInfoUpdated` for the current media or of
+    /// `PlaylistComponentOutput::SelectedMediaInfo` for the selected playlist entry.
+    SetMediaInfo(Option<MediaInfo>),
+    CopyToClipboard,
+}
@@ -0,0 +1,12 @@
+use crate::media_info::MediaInfo;
+
+#[derive(Debug, Default)]
+pub struct MediaInfoComponentInit {
+    pub media_info: Option<MediaInfo>,
+}
+
+#[derive(Debug)]
+pub struct MediaInfoComponentModel {
+    pub(super) media_info: Option<MediaInfo>,
+    pub(super) text: String,
+}
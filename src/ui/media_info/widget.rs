@@ -0,0 +1,195 @@
+use log::*;
+use mxl_relm4_components::relm4::{self, gtk::prelude::*, prelude::*};
+use relm4_icons::icon_names;
+
+use super::{
+    messages::MediaInfoComponentInput,
+    model::{MediaInfoComponentInit, MediaInfoComponentModel},
+};
+use crate::{
+    localization::helper::fl,
+    media_info::{MediaInfo, MediaInfoLabels, StreamType},
+};
+
+#[relm4::component(pub)]
+impl Component for MediaInfoComponentModel {
+    type Init = MediaInfoComponentInit;
+    type Input = MediaInfoComponentInput;
+    type Output = ();
+    type CommandOutput = ();
+
+    view! {
+        gtk::Box {
+            set_orientation: gtk::Orientation::Vertical,
+            set_spacing: 5,
+
+            gtk::Box {
+                set_orientation: gtk::Orientation::Horizontal,
+                set_spacing: 5,
+
+                gtk::Label {
+                    set_label: &fl!("media-info"),
+                    set_hexpand: true,
+                    set_halign: gtk::Align::Start,
+                    add_css_class: "heading",
+                },
+                gtk::Button {
+                    set_icon_name: icon_names::COPY,
+                    set_tooltip_text: Some(&fl!("media-info", "copy")),
+                    #[watch]
+                    set_sensitive: model.media_info.is_some(),
+                    connect_clicked => MediaInfoComponentInput::CopyToClipboard,
+                },
+            },
+            gtk::ScrolledWindow {
+                set_vexpand: true,
+                set_hscrollbar_policy: gtk::PolicyType::Automatic,
+                set_vscrollbar_policy: gtk::PolicyType::Automatic,
+
+                gtk::Label {
+                    set_halign: gtk::Align::Start,
+                    set_valign: gtk::Align::Start,
+                    set_selectable: true,
+                    add_css_class: "monospace",
+                    #[watch]
+                    set_label: &model.text,
+                },
+            },
+        }
+    }
+
+    // Initialize the component.
+    fn init(init: Self::Init, root: Self::Root, _sender: ComponentSender<Self>) -> ComponentParts<Self> {
+        let model = MediaInfoComponentModel {
+            text: media_info_text(init.media_info.as_ref()),
+            media_info: init.media_info,
+        };
+
+        let widgets = view_output!();
+
+        ComponentParts { model, widgets }
+    }
+
+    fn update(&mut self, msg: Self::Input, _sender: ComponentSender<Self>, root: &Self::Root) {
+        match msg {
+            MediaInfoComponentInput::SetMediaInfo(media_info) => {
+                self.text = media_info_text(media_info.as_ref());
+                self.media_info = media_info;
+            }
+            MediaInfoComponentInput::CopyToClipboard => {
+                if self.media_info.is_some() {
+                    debug!("copy media info to clipboard");
+                    root.clipboard().set_text(&self.text);
+                }
+            }
+        }
+    }
+}
+
+fn media_info_text(media_info: Option<&MediaInfo>) -> String {
+    match media_info {
+        Some(media_info) => media_info.to_tree_with(&LocalizedLabels).to_string(),
+        None => fl!("media-info", "none"),
+    }
+}
+
+/// Localized labels of the media info tree.
+struct LocalizedLabels;
+
+impl MediaInfoLabels for LocalizedLabels {
+    fn uri(&self, uri: &str) -> String {
+        fl!("media-info", "uri", uri = uri)
+    }
+
+    fn title(&self, title: &str) -> String {
+        fl!("media-info", "title", title = title)
+    }
+
+    fn container(&self, container: &str) -> String {
+        fl!("media-info", "container", container = container)
+    }
+
+    fn duration(&self, duration: f64) -> String {
+        fl!("media-info", "duration", duration = format!("{duration:.3}"))
+    }
+
+    fn live(&self, live: bool) -> String {
+        fl!("media-info", "live", live = yes_no(live))
+    }
+
+    fn seekable(&self, seekable: bool) -> String {
+        fl!("media-info", "seekable", seekable = yes_no(seekable))
+    }
+
+    fn stream(&self, index: usize) -> String {
+        fl!("media-info", "stream", index = index)
+    }
+
+    fn stream_type(&self, stream_type: StreamType) -> String {
+        let kind = match stream_type {
+            StreamType::Video => fl!("media-info", "video"),
+            StreamType::Audio => fl!("media-info", "audio"),
+            StreamType::Subtitle => fl!("media-info", "subtitle"),
+            StreamType::Other => fl!("media-info", "other"),
+        };
+        fl!("media-info", "type", kind = kind)
+    }
+
+    fn codec(&self, codec: &str) -> String {
+        fl!("media-info", "codec", codec = codec)
+    }
+
+    fn language(&self, language: &str) -> String {
+        fl!("media-info", "language", language = language)
+    }
+
+    fn resolution(&self, width: u32, height: u32) -> String {
+        fl!("media-info", "resolution", width = width, height = height)
+    }
+
+    fn framerate(&self, framerate: f64) -> String {
+        fl!("media-info", "framerate", framerate = format!("{framerate:.3}"))
+    }
+
+    fn sample_rate(&self, sample_rate: u32) -> String {
+        fl!("media-info", "sample-rate", rate = sample_rate)
+    }
+
+    fn channels(&self, channels: u32) -> String {
+        fl!("media-info", "channels", channels = channels)
+    }
+
+    fn bitrate(&self, bitrate: u32) -> String {
+        fl!("media-info", "bitrate", bitrate = bitrate)
+    }
+
+    fn chapters(&self) -> String {
+        fl!("media-info", "chapters")
+    }
+
+    fn chapter(&self, title: &str, start: f64, stop: Option<f64>) -> String {
+        let start = format!("{start:.3}");
+        match stop {
+            Some(stop) => fl!(
+                "media-info",
+                "chapter",
+                title = title,
+                start = start,
+                stop = format!("{stop:.3}")
+            ),
+            None => fl!("media-info", "chapter-open", title = title, start = start),
+        }
+    }
+
+    fn tags(&self) -> String {
+        fl!("media-info", "tags")
+    }
+}
+
+fn yes_no(value: bool) -> String {
+    if value {
+        fl!("media-info", "yes")
+    } else {
+        fl!("media-info", "no")
+    }
+}
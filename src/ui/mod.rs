@@ -1,4 +1,6 @@
+pub mod audio_level_meter;
 pub mod codec_ranking;
+pub mod media_info;
 pub mod message_dialog;
 pub mod player;
 pub mod playlist;
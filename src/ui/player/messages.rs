@@ -1,4 +1,7 @@
-use gst_play::PlayMediaInfo;
+use gst_play::{prelude::*, PlayMediaInfo, PlaySubtitleInfo, PlayVideoInfo};
+use std::path::PathBuf;
+
+use crate::{chapter::Chapter, error::PlayerError, media_info::MediaInfo, player::FilterSpec};
 
 #[derive(Debug)]
 pub enum Track {
@@ -7,21 +10,158 @@ pub enum Track {
     Stream(i32),
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorBalanceChannel {
+    Brightness,
+    Contrast,
+    Hue,
+    Saturation,
+}
+
+impl From<ColorBalanceChannel> for gst_play::PlayColorBalanceType {
+    fn from(channel: ColorBalanceChannel) -> Self {
+        match channel {
+            ColorBalanceChannel::Brightness => Self::Brightness,
+            ColorBalanceChannel::Contrast => Self::Contrast,
+            ColorBalanceChannel::Hue => Self::Hue,
+            ColorBalanceChannel::Saturation => Self::Saturation,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum DeinterlaceMode {
+    /// Deinterlace only interlaced content.
+    #[default]
+    Auto,
+    /// Force deinterlacing, e.g. for content with wrong interlacing flags.
+    Interlaced,
+    Disabled,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TrickMode {
+    /// Decode all frames at speeds other than 1.0.
+    #[default]
+    Accurate,
+    /// Decode only key frames at speeds other than 1.0, which allows fast playback speeds like 8x or 16x.
+    KeyUnits,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnapshotFormat {
+    Png,
+    Jpeg,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VideoStream {
+    pub index: i32,
+    pub width: i32,
+    pub height: i32,
+    pub framerate: Option<gst::Fraction>,
+    pub codec: Option<String>,
+}
+
+impl From<&PlayVideoInfo> for VideoStream {
+    fn from(info: &PlayVideoInfo) -> Self {
+        let framerate = info.framerate();
+        Self {
+            index: info.index(),
+            width: info.width(),
+            height: info.height(),
+            framerate: if framerate.numer() > 0 && framerate.denom() > 0 {
+                Some(framerate)
+            } else {
+                None
+            },
+            codec: info.codec().map(|x| x.to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubtitleStream {
+    pub index: i32,
+    pub language: Option<String>,
+    pub title: Option<String>,
+    pub codec: Option<String>,
+}
+
+impl From<&PlaySubtitleInfo> for SubtitleStream {
+    fn from(info: &PlaySubtitleInfo) -> Self {
+        Self {
+            index: info.index(),
+            language: info.language().map(|x| x.to_string()),
+            title: info
+                .tags()
+                .and_then(|tags| tags.get::<gst::tags::Title>().map(|x| x.get().to_string())),
+            codec: info.codec().map(|x| x.to_string()),
+        }
+    }
+}
+
+/// Audio level of each channel in dB.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AudioLevel {
+    pub rms: Vec<f64>,
+    pub peak: Vec<f64>,
+    pub decay: Vec<f64>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Statistics {
+    pub rendered_frames: u64,
+    pub dropped_frames: u64,
+    /// Rendered frames per second since the previous statistics.
+    pub fps: Option<f64>,
+    /// Factory name of the video decoder element.
+    pub video_decoder: Option<String>,
+    /// Measured input bitrate in bits per second, only available for buffered media.
+    pub bitrate: Option<u64>,
+    pub video_caps: Option<String>,
+}
+
 #[derive(Debug)]
 pub enum PlayerComponentInput {
     UpdateUri(String),
+    /// URI to continue with gaplessly, when the current URI is about to finish.
+    SetNextUri(Option<String>),
     ChangeState(PlaybackState),
     SwitchAudioTrack(Track),
+    SwitchVideoTrack(Track),
+    SetVideoStreamVisible(i32, bool),
+    SwitchSubtitleTrack(Track),
+    SetSubtitleUri(Option<String>),
     Seek(f64),
+    SetLoopStart(Option<f64>),
+    SetLoopEnd(Option<f64>),
+    ClearLoop,
     NextFrame,
+    PreviousFrame,
+    NextChapter,
+    PreviousChapter,
+    Snapshot {
+        path: PathBuf,
+        format: SnapshotFormat,
+        size: Option<(i32, i32)>,
+    },
     SetVolume(f64),
     SetSpeed(f64),
+    SetTrickMode(TrickMode),
+    SetPreservePitch(bool),
     DumpPipeline(String),
     SetZoomRelative(f64),
     SetZoom(Option<f64>),
     SetAudioVideoOffset(i64),
     SetSubtitleVideoOffset(i64),
+    SetColorBalance(ColorBalanceChannel, f64),
+    SetGamma(f64),
+    SetDeinterlaceMode(DeinterlaceMode),
+    SetVideoFilter(Option<FilterSpec>),
+    SetAudioFilter(Option<FilterSpec>),
     SetOverlayVisible(bool),
+    SetStatisticsVisible(bool),
     RequestOverlayRedraw,
     ReloadPlayer,
     PrivateMessage(internal::PrivateMsg),
@@ -39,18 +179,37 @@ pub enum PlaybackState {
 #[derive(Debug)]
 pub enum PlayerComponentOutput {
     PlayerInitialized(Option<anyhow::Error>),
-    MediaInfoUpdated(PlayMediaInfo),
+    MediaInfoUpdated(MediaInfo),
+    VideoStreamsChanged(Vec<VideoStream>),
+    SubtitleStreamsChanged(Vec<SubtitleStream>),
     DurationChanged(f64),
     PositionUpdated(f64),
     SeekDone,
+    Buffering(u32),
+    BufferedRangesChanged(Vec<(f64, f64)>),
+    LoopRegionChanged(Option<(f64, f64)>),
+    ChaptersChanged(Vec<Chapter>),
+    /// Index of the chapter at the current position.
+    ChapterChanged(Option<usize>),
+    /// The playback continued gaplessly with the next URI.
+    GaplessSwitched(String),
     EndOfStream(std::string::String),
     StateChanged(Option<PlaybackState>, PlaybackState),
     VolumeChanged(f64),
     SpeedChanged(f64),
+    /// Whether the audio pitch is preserved at playback speeds other than 1.0.
+    PreservePitchChanged(bool),
+    ReversePlaybackSupported(bool),
     AudioVideoOffsetChanged(i64),
     SubtitleVideoOffsetChanged(i64),
-    Warning(anyhow::Error),
-    Error(anyhow::Error),
+    AudioLevel(AudioLevel),
+    /// Periodic statistics of the playback, which are also sent while paused or buffering.
+    Statistics(Statistics),
+    SnapshotSaved(PathBuf),
+    /// Taking or saving a snapshot failed, which does not affect the playback.
+    SnapshotFailed(PlayerError),
+    Warning(PlayerError),
+    Error(PlayerError),
 }
 
 #[derive(Debug)]
@@ -59,14 +218,21 @@ pub enum PlayerComponentCommand {
     PositionUpdated(f64),
     DurationChanged(f64),
     SeekDone,
+    Buffering(u32),
+    ChaptersChanged(Vec<Chapter>),
+    LoopRegionChanged(Option<(f64, f64)>),
+    GaplessSwitched(String),
     EndOfStream(std::string::String),
     StateChanged(Option<PlaybackState>, PlaybackState),
     VideoDimensionsChanged(i32, i32),
     VolumeChanged(f64),
     AudioVideoOffsetChanged(i64),
     SubtitleVideoOffsetChanged(i64),
-    Warning(anyhow::Error),
-    Error(anyhow::Error),
+    AudioLevel(AudioLevel),
+    SnapshotSaved(PathBuf),
+    SnapshotFailed(PlayerError),
+    Warning(PlayerError),
+    Error(PlayerError),
 }
 
 pub(super) mod internal {
@@ -76,5 +242,6 @@ pub(super) mod internal {
         DragUpdate(f64, f64),
         DragEnd(f64, f64),
         MotionDetected(f64, f64),
+        UpdateStatistics,
     }
 }
@@ -1,5 +1,6 @@
-use crate::player::{Player, PlayerBuilder};
-use crate::ui::player::messages::PlaybackState;
+use crate::media_info::MediaInfo;
+use crate::player::{FilterSpec, Player, PlayerBuilder};
+use crate::ui::player::messages::{PlaybackState, Statistics, SubtitleStream, VideoStream};
 use log::*;
 use mxl_relm4_components::relm4::{gtk, gtk::prelude::*};
 use std::{rc::Rc, sync::Mutex};
@@ -9,10 +10,38 @@ type DrawCallbackFn = dyn Fn(&gtk::cairo::Context, &mut VideoViewData);
 pub struct PlayerComponentInit {
     pub seek_accurate: bool,
     pub show_seeking_overlay: bool,
+    pub show_statistics: bool,
+    pub audio_level_meter: bool,
+    pub gamma: bool,
+    pub preserve_pitch: bool,
     pub compositor: Option<gst::Element>,
+    pub video_filter: Option<FilterSpec>,
+    pub audio_filter: Option<FilterSpec>,
     pub draw_callback: Box<DrawCallbackFn>,
     pub drag_gesture: Option<gtk::GestureDrag>,
     pub motion_tracker: Option<gtk::EventControllerMotion>,
+    /// Seek bar, which is marked with the starts of the chapters.
+    pub seek_bar: Option<gtk::Scale>,
+}
+
+impl Default for PlayerComponentInit {
+    fn default() -> Self {
+        Self {
+            seek_accurate: false,
+            show_seeking_overlay: false,
+            show_statistics: false,
+            audio_level_meter: false,
+            gamma: false,
+            preserve_pitch: false,
+            compositor: None,
+            video_filter: None,
+            audio_filter: None,
+            draw_callback: Box::new(|_, _| {}),
+            drag_gesture: None,
+            motion_tracker: None,
+            seek_bar: None,
+        }
+    }
 }
 
 #[derive(Debug, Default)]
@@ -48,9 +77,22 @@ pub struct PlayerComponentModel {
     pub(super) player_builder: PlayerBuilder,
     pub(super) player: Option<Player>,
     pub(super) playback_state: PlaybackState,
+    pub(super) video_streams: Vec<VideoStream>,
+    pub(super) subtitle_streams: Vec<SubtitleStream>,
+    pub(super) reverse_playback_supported: bool,
+    pub(super) current_chapter: Option<usize>,
+    /// Media info of the current URI, which is completed by the chapters.
+    pub(super) media_info: Option<MediaInfo>,
+    pub(super) seek_bar: Option<gtk::Scale>,
     pub(super) show_seeking_overlay: bool,
     pub(super) seeking: bool,
+    pub(super) buffering_percent: u32,
+    pub(super) buffered_ranges: Vec<(f64, f64)>,
     pub(super) show_drawing_overlay: bool,
+    pub(super) show_statistics: bool,
+    pub(super) statistics: Option<Statistics>,
+    /// Timer of the periodic statistics, which runs while the player is not stopped.
+    pub(super) statistics_timer: Option<gtk::glib::SourceId>,
     pub(super) view_data: Rc<Mutex<ViewData>>,
     pub(super) draw_callback: Rc<Mutex<DrawCallbackData>>,
     pub(super) drag_position: Option<(f64, f64)>,
@@ -1,20 +1,24 @@
 use gst_video::VideoRectangle;
 use log::*;
 use mxl_relm4_components::relm4::{self, gtk::glib, gtk::prelude::*, prelude::*};
-use std::{borrow::BorrowMut, rc::Rc, sync::Mutex};
+use std::{borrow::BorrowMut, rc::Rc, sync::Mutex, time::Duration};
 
 use glib::clone;
 
 use super::{
     messages::{
         internal::PrivateMsg, PlaybackState, PlayerComponentCommand, PlayerComponentInput, PlayerComponentOutput,
+        Statistics, SubtitleStream, VideoStream,
     },
     model::{PlayerComponentInit, PlayerComponentModel, ViewData},
 };
+use crate::chapter::Chapter;
+use crate::media_info::MediaInfo;
 use crate::player::PlayerBuilder;
 use crate::{localization::helper::fl, ui::player::model::DrawCallbackData};
 
 const SCALE_MULTIPLIER: f64 = 2.0;
+const STATISTICS_INTERVAL: Duration = Duration::from_secs(1);
 
 #[relm4::component(pub)]
 impl Component for PlayerComponentModel {
@@ -53,6 +57,21 @@ impl Component for PlayerComponentModel {
                 },
             },
 
+            add_overlay = &gtk::Label {
+                #[watch]
+                set_visible: model.show_statistics && model.statistics.is_some(),
+                #[watch]
+                set_label: &model.statistics.as_ref().map(statistics_text).unwrap_or_default(),
+                add_css_class: "osd",
+                add_css_class: "monospace",
+                set_halign: gtk::Align::Start,
+                set_valign: gtk::Align::Start,
+                set_xalign: 0.0,
+                set_wrap: true,
+                set_margin_all: 8,
+                set_can_target: false,
+            },
+
             add_overlay = overlay = &gtk::Box {
                 #[watch]
                 set_visible: model.show_seeking_overlay && model.playback_state == PlaybackState::Buffering,
@@ -80,10 +99,23 @@ impl Component for PlayerComponentModel {
                     },
 
                     gtk::Spinner {
+                        #[watch]
+                        set_visible: model.seeking,
                         #[watch]
                         set_spinning: overlay.is_visible(),
                         set_size_request: (20, 20),
                     },
+
+                    gtk::ProgressBar {
+                        #[watch]
+                        set_visible: !model.seeking,
+                        #[watch]
+                        set_fraction: model.buffering_percent as f64 / 100.0,
+                        #[watch]
+                        set_text: Some(&format!("{} %", model.buffering_percent)),
+                        set_show_text: true,
+                        set_width_request: 120,
+                    },
                 },
             },
         }
@@ -95,7 +127,12 @@ impl Component for PlayerComponentModel {
 
         player_builder
             .seek_accurate(init.seek_accurate)
-            .compositor(init.compositor);
+            .compositor(init.compositor)
+            .video_filter(init.video_filter)
+            .audio_filter(init.audio_filter)
+            .audio_level_meter(init.audio_level_meter)
+            .gamma(init.gamma)
+            .preserve_pitch(init.preserve_pitch);
 
         let player = match player_builder.build(sender.command_sender().clone()) {
             Ok(player) => {
@@ -118,9 +155,20 @@ impl Component for PlayerComponentModel {
             player_builder,
             player,
             playback_state: PlaybackState::Stopped,
+            video_streams: Vec::new(),
+            subtitle_streams: Vec::new(),
+            reverse_playback_supported: false,
+            current_chapter: None,
+            media_info: None,
+            seek_bar: init.seek_bar,
             show_seeking_overlay: init.show_seeking_overlay,
             seeking: false,
+            buffering_percent: 0,
+            buffered_ranges: Vec::new(),
             show_drawing_overlay: false,
+            show_statistics: init.show_statistics,
+            statistics: None,
+            statistics_timer: None,
             view_data: Rc::new(Mutex::new(ViewData::default())),
             draw_callback: Rc::new(Mutex::new(DrawCallbackData::new(init.draw_callback))),
             drag_position: None,
@@ -131,7 +179,7 @@ impl Component for PlayerComponentModel {
         let widgets = view_output!();
 
         if let Some(player) = &model.player {
-            widgets.video_picture.set_paintable(Some(player.paintable()).as_ref());
+            widgets.video_picture.set_paintable(player.paintable().as_ref());
         }
 
         {
@@ -204,7 +252,27 @@ impl Component for PlayerComponentModel {
         if let Some(player) = &self.player {
             match msg {
                 PlayerComponentInput::UpdateUri(uri) => {
+                    let had_loop_region = player.loop_region().is_some();
+                    let had_chapters = !player.chapters().is_empty();
                     player.set_uri(&uri);
+                    self.media_info = None;
+                    self.buffering_percent = 0;
+                    if had_chapters {
+                        self.update_seek_bar_marks(&[]);
+                        sender
+                            .output(PlayerComponentOutput::ChaptersChanged(Vec::new()))
+                            .unwrap_or_default();
+                    }
+                    self.update_current_chapter(None, &sender);
+                    self.update_buffered_ranges(&sender);
+                    if had_loop_region {
+                        sender
+                            .output(PlayerComponentOutput::LoopRegionChanged(None))
+                            .unwrap_or_default();
+                    }
+                }
+                PlayerComponentInput::SetNextUri(uri) => {
+                    player.set_next_uri(uri.as_deref());
                 }
                 PlayerComponentInput::ChangeState(state) => match state {
                     PlaybackState::Playing => player.play(),
@@ -215,26 +283,133 @@ impl Component for PlayerComponentModel {
                 },
                 PlayerComponentInput::SwitchAudioTrack(track) => {
                     if let Err(error) = player.set_audio_track(track) {
-                        sender.output(PlayerComponentOutput::Error(error)).unwrap_or_default();
+                        sender
+                            .output(PlayerComponentOutput::Error(error.into()))
+                            .unwrap_or_default();
+                    }
+                }
+                PlayerComponentInput::SwitchVideoTrack(track) => {
+                    if let Err(error) = player.set_video_track(track) {
+                        sender
+                            .output(PlayerComponentOutput::Error(error.into()))
+                            .unwrap_or_default();
+                    }
+                }
+                PlayerComponentInput::SetVideoStreamVisible(index, visible) => {
+                    if let Err(error) = player.set_video_stream_visible(index, visible) {
+                        sender
+                            .output(PlayerComponentOutput::Error(error.into()))
+                            .unwrap_or_default();
+                    }
+                }
+                PlayerComponentInput::SwitchSubtitleTrack(track) => {
+                    if let Err(error) = player.set_subtitle_track(track) {
+                        sender
+                            .output(PlayerComponentOutput::Error(error.into()))
+                            .unwrap_or_default();
                     }
                 }
+                PlayerComponentInput::SetSubtitleUri(uri) => {
+                    player.set_subtitle_uri(uri.as_deref());
+                }
                 PlayerComponentInput::Seek(to) => {
                     self.seeking = true;
                     player.seek(&to);
                 }
+                PlayerComponentInput::SetLoopStart(position) => {
+                    if let Err(error) = player.set_loop_start(position) {
+                        sender
+                            .output(PlayerComponentOutput::Warning(error.into()))
+                            .unwrap_or_default();
+                    }
+                    sender
+                        .output(PlayerComponentOutput::LoopRegionChanged(player.loop_region()))
+                        .unwrap_or_default();
+                }
+                PlayerComponentInput::SetLoopEnd(position) => {
+                    if let Err(error) = player.set_loop_end(position) {
+                        sender
+                            .output(PlayerComponentOutput::Warning(error.into()))
+                            .unwrap_or_default();
+                    }
+                    sender
+                        .output(PlayerComponentOutput::LoopRegionChanged(player.loop_region()))
+                        .unwrap_or_default();
+                }
+                PlayerComponentInput::ClearLoop => {
+                    if let Err(error) = player.clear_loop() {
+                        sender
+                            .output(PlayerComponentOutput::Warning(error.into()))
+                            .unwrap_or_default();
+                    }
+                    sender
+                        .output(PlayerComponentOutput::LoopRegionChanged(None))
+                        .unwrap_or_default();
+                }
                 PlayerComponentInput::NextFrame => {
                     player.next_frame();
                 }
+                PlayerComponentInput::NextChapter => {
+                    if let Err(error) = player.next_chapter() {
+                        sender
+                            .output(PlayerComponentOutput::Warning(error.into()))
+                            .unwrap_or_default();
+                    }
+                }
+                PlayerComponentInput::PreviousChapter => {
+                    if let Err(error) = player.previous_chapter() {
+                        sender
+                            .output(PlayerComponentOutput::Warning(error.into()))
+                            .unwrap_or_default();
+                    }
+                }
+                PlayerComponentInput::PreviousFrame => {
+                    if let Err(error) = player.previous_frame() {
+                        sender
+                            .output(PlayerComponentOutput::Warning(error.into()))
+                            .unwrap_or_default();
+                    }
+                }
+                PlayerComponentInput::Snapshot { path, format, size } => {
+                    let saver = player.snapshot_saver();
+                    sender.spawn_oneshot_command(move || match saver.save(&path, format, size) {
+                        Ok(()) => PlayerComponentCommand::SnapshotSaved(path),
+                        Err(error) => PlayerComponentCommand::SnapshotFailed(error.into()),
+                    });
+                }
                 PlayerComponentInput::SetVolume(vol) => {
                     player.set_volume(vol);
                 }
                 PlayerComponentInput::SetSpeed(speed) => {
                     let current_speed = player.speed();
-                    player.set_speed(speed);
-                    if current_speed != speed {
-                        sender
-                            .output(PlayerComponentOutput::SpeedChanged(speed))
-                            .unwrap_or_default();
+                    match player.set_speed(speed) {
+                        Ok(()) => {
+                            if current_speed != speed {
+                                sender
+                                    .output(PlayerComponentOutput::SpeedChanged(speed))
+                                    .unwrap_or_default();
+                            }
+                        }
+                        Err(error) => sender
+                            .output(PlayerComponentOutput::Warning(error.into()))
+                            .unwrap_or_default(),
+                    }
+                }
+                PlayerComponentInput::SetTrickMode(mode) => player.set_trick_mode(mode),
+                PlayerComponentInput::SetPreservePitch(preserve_pitch) => {
+                    let changed = preserve_pitch != player.preserve_pitch();
+                    match player.set_preserve_pitch(preserve_pitch) {
+                        Ok(()) => {
+                            self.player_builder.preserve_pitch(preserve_pitch);
+                            if changed {
+                                sender
+                                    .output(PlayerComponentOutput::PreservePitchChanged(preserve_pitch))
+                                    .unwrap_or_default();
+                            }
+                        }
+                        Err(error) => sender
+                            .output(PlayerComponentOutput::Warning(error.into()))
+                            .unwrap_or_default(),
                     }
                 }
                 PlayerComponentInput::DumpPipeline(label) => {
@@ -265,19 +440,51 @@ impl Component for PlayerComponentModel {
                     self.player_builder.subtitle_offset(offset);
                     player.set_subtitle_video_offset(offset);
                 }
+                PlayerComponentInput::SetColorBalance(channel, value) => {
+                    if let Err(error) = player.set_color_balance(channel, value) {
+                        sender
+                            .output(PlayerComponentOutput::Warning(error.into()))
+                            .unwrap_or_default();
+                    }
+                }
+                PlayerComponentInput::SetGamma(value) => {
+                    if let Err(error) = player.set_gamma(value) {
+                        sender
+                            .output(PlayerComponentOutput::Warning(error.into()))
+                            .unwrap_or_default();
+                    }
+                }
+                PlayerComponentInput::SetDeinterlaceMode(mode) => player.set_deinterlace_mode(mode),
+                PlayerComponentInput::SetVideoFilter(filter) => match player.set_video_filter(filter.clone()) {
+                    Ok(()) => {
+                        self.player_builder.video_filter(filter);
+                    }
+                    Err(error) => sender
+                        .output(PlayerComponentOutput::Warning(error.into()))
+                        .unwrap_or_default(),
+                },
+                PlayerComponentInput::SetAudioFilter(filter) => match player.set_audio_filter(filter.clone()) {
+                    Ok(()) => {
+                        self.player_builder.audio_filter(filter);
+                    }
+                    Err(error) => sender
+                        .output(PlayerComponentOutput::Warning(error.into()))
+                        .unwrap_or_default(),
+                },
                 PlayerComponentInput::SetOverlayVisible(visible) => {
                     self.show_drawing_overlay = visible;
                     widgets.drawing_overlay.queue_draw();
                 }
+                PlayerComponentInput::SetStatisticsVisible(visible) => self.show_statistics = visible,
                 PlayerComponentInput::RequestOverlayRedraw => widgets.drawing_overlay.queue_draw(),
                 PlayerComponentInput::ReloadPlayer => {
                     self.player = match self.player_builder.build(sender.command_sender().clone()) {
                         Ok(player) => {
-                            widgets.video_picture.set_paintable(Some(player.paintable()).as_ref());
+                            widgets.video_picture.set_paintable(player.paintable().as_ref());
                             Some(player)
                         }
                         Err(error) => {
-                            sender.output_sender().emit(PlayerComponentOutput::Error(error));
+                            sender.output_sender().emit(PlayerComponentOutput::Error(error.into()));
                             None
                         }
                     };
@@ -286,6 +493,7 @@ impl Component for PlayerComponentModel {
                     PrivateMsg::MotionDetected(x, y) => {
                         self.mouse_position = Some((x, y));
                     }
+                    PrivateMsg::UpdateStatistics => self.update_statistics(&sender),
                     PrivateMsg::DragBegin(_, _) => {
                         // Start the drag position at 0.0, 0.0:
                         self.drag_position = Some((0.0, 0.0));
@@ -328,6 +536,10 @@ impl Component for PlayerComponentModel {
         self.update_view(widgets, sender)
     }
 
+    fn shutdown(&mut self, _widgets: &mut Self::Widgets, _output: relm4::Sender<Self::Output>) {
+        self.stop_statistics_timer();
+    }
+
     fn update_cmd(&mut self, msg: Self::CommandOutput, sender: ComponentSender<Self>, _root: &Self::Root) {
         match msg {
             PlayerComponentCommand::VideoDimensionsChanged(width, height) => {
@@ -342,8 +554,37 @@ impl Component for PlayerComponentModel {
                 }
             }
             PlayerComponentCommand::MediaInfoUpdated(info) => {
+                let reverse_playback_supported = self
+                    .player
+                    .as_ref()
+                    .is_some_and(|player| player.supports_reverse_playback());
+                if reverse_playback_supported != self.reverse_playback_supported {
+                    self.reverse_playback_supported = reverse_playback_supported;
+                    sender
+                        .output(PlayerComponentOutput::ReversePlaybackSupported(
+                            reverse_playback_supported,
+                        ))
+                        .unwrap_or_default();
+                }
+                let video_streams: Vec<_> = info.video_streams().iter().map(VideoStream::from).collect();
+                if video_streams != self.video_streams {
+                    self.video_streams = video_streams.clone();
+                    sender
+                        .output(PlayerComponentOutput::VideoStreamsChanged(video_streams))
+                        .unwrap_or_default();
+                }
+                let subtitle_streams: Vec<_> = info.subtitle_streams().iter().map(SubtitleStream::from).collect();
+                if subtitle_streams != self.subtitle_streams {
+                    self.subtitle_streams = subtitle_streams.clone();
+                    sender
+                        .output(PlayerComponentOutput::SubtitleStreamsChanged(subtitle_streams))
+                        .unwrap_or_default();
+                }
+                let mut media_info = MediaInfo::from(&info);
+                media_info.chapters = self.player.as_ref().map(|player| player.chapters()).unwrap_or_default();
+                self.media_info = Some(media_info.clone());
                 sender
-                    .output(PlayerComponentOutput::MediaInfoUpdated(info))
+                    .output(PlayerComponentOutput::MediaInfoUpdated(media_info))
                     .unwrap_or_default();
             }
             PlayerComponentCommand::DurationChanged(duration) => {
@@ -352,14 +593,57 @@ impl Component for PlayerComponentModel {
                     .unwrap_or_default();
             }
             PlayerComponentCommand::PositionUpdated(pos) => {
+                self.update_buffered_ranges(&sender);
+                self.update_current_chapter(Some(pos), &sender);
                 sender
                     .output(PlayerComponentOutput::PositionUpdated(pos))
                     .unwrap_or_default();
             }
+            PlayerComponentCommand::ChaptersChanged(chapters) => {
+                let position = self.player.as_ref().and_then(|player| player.position());
+                self.update_seek_bar_marks(&chapters);
+                // The table of contents may be read after the media info was updated:
+                let uri = self.player.as_ref().and_then(|player| player.uri());
+                if let Some(media_info) = self
+                    .media_info
+                    .as_mut()
+                    .filter(|info| uri.as_ref() == Some(&info.uri) && info.chapters != chapters)
+                {
+                    media_info.chapters = chapters.clone();
+                    sender
+                        .output(PlayerComponentOutput::MediaInfoUpdated(media_info.clone()))
+                        .unwrap_or_default();
+                }
+                sender
+                    .output(PlayerComponentOutput::ChaptersChanged(chapters))
+                    .unwrap_or_default();
+                self.update_current_chapter(position, &sender);
+            }
             PlayerComponentCommand::SeekDone => {
                 self.seeking = false;
                 sender.output(PlayerComponentOutput::SeekDone).unwrap_or_default();
             }
+            PlayerComponentCommand::Buffering(percent) => {
+                self.buffering_percent = percent;
+                self.update_buffered_ranges(&sender);
+                sender
+                    .output(PlayerComponentOutput::Buffering(percent))
+                    .unwrap_or_default();
+            }
+            PlayerComponentCommand::LoopRegionChanged(loop_region) => {
+                sender
+                    .output(PlayerComponentOutput::LoopRegionChanged(loop_region))
+                    .unwrap_or_default();
+            }
+            PlayerComponentCommand::GaplessSwitched(uri) => {
+                self.media_info = None;
+                self.buffering_percent = 0;
+                self.update_current_chapter(None, &sender);
+                self.update_buffered_ranges(&sender);
+                sender
+                    .output(PlayerComponentOutput::GaplessSwitched(uri))
+                    .unwrap_or_default();
+            }
             PlayerComponentCommand::EndOfStream(val) => {
                 sender
                     .output(PlayerComponentOutput::EndOfStream(val))
@@ -376,6 +660,9 @@ impl Component for PlayerComponentModel {
                 };
                 if reset_states {
                     self.seeking = false;
+                    self.stop_statistics_timer();
+                } else {
+                    self.start_statistics_timer(&sender);
                 }
                 sender.input_sender().emit(PlayerComponentInput::RequestOverlayRedraw);
                 sender
@@ -397,6 +684,21 @@ impl Component for PlayerComponentModel {
                     .output(PlayerComponentOutput::SubtitleVideoOffsetChanged(offset))
                     .unwrap_or_default();
             }
+            PlayerComponentCommand::AudioLevel(level) => {
+                sender
+                    .output(PlayerComponentOutput::AudioLevel(level))
+                    .unwrap_or_default();
+            }
+            PlayerComponentCommand::SnapshotSaved(path) => {
+                sender
+                    .output(PlayerComponentOutput::SnapshotSaved(path))
+                    .unwrap_or_default();
+            }
+            PlayerComponentCommand::SnapshotFailed(error) => {
+                sender
+                    .output(PlayerComponentOutput::SnapshotFailed(error))
+                    .unwrap_or_default();
+            }
             PlayerComponentCommand::Warning(error) => {
                 sender.output(PlayerComponentOutput::Warning(error)).unwrap_or_default();
             }
@@ -408,6 +710,73 @@ impl Component for PlayerComponentModel {
 }
 
 impl PlayerComponentModel {
+    /// Replaces the marks of the seek bar with the starts of the chapters.
+    fn update_seek_bar_marks(&self, chapters: &[Chapter]) {
+        if let Some(seek_bar) = &self.seek_bar {
+            seek_bar.clear_marks();
+            for chapter in chapters {
+                let markup = chapter.title.as_deref().map(glib::markup_escape_text);
+                seek_bar.add_mark(chapter.start, gtk::PositionType::Bottom, markup.as_deref());
+            }
+        }
+    }
+
+    fn update_current_chapter(&mut self, position: Option<f64>, sender: &ComponentSender<Self>) {
+        let current_chapter = self
+            .player
+            .as_ref()
+            .zip(position)
+            .and_then(|(player, position)| player.chapter_at(position));
+        if current_chapter != self.current_chapter {
+            self.current_chapter = current_chapter;
+            sender
+                .output(PlayerComponentOutput::ChapterChanged(current_chapter))
+                .unwrap_or_default();
+        }
+    }
+
+    /// Updates the statistics periodically, also while paused or buffering, when no positions are reported.
+    fn start_statistics_timer(&mut self, sender: &ComponentSender<Self>) {
+        if self.statistics_timer.is_none() {
+            let sender = sender.clone();
+            self.statistics_timer = Some(glib::timeout_add_local(STATISTICS_INTERVAL, move || {
+                sender.input(PlayerComponentInput::PrivateMessage(PrivateMsg::UpdateStatistics));
+                glib::ControlFlow::Continue
+            }));
+        }
+    }
+
+    fn stop_statistics_timer(&mut self) {
+        if let Some(timer) = self.statistics_timer.take() {
+            timer.remove();
+        }
+    }
+
+    fn update_statistics(&mut self, sender: &ComponentSender<Self>) {
+        let Some(player) = &self.player else {
+            return;
+        };
+        let statistics = player.statistics();
+        self.statistics = Some(statistics.clone());
+        sender
+            .output(PlayerComponentOutput::Statistics(statistics))
+            .unwrap_or_default();
+    }
+
+    fn update_buffered_ranges(&mut self, sender: &ComponentSender<Self>) {
+        let buffered_ranges = self
+            .player
+            .as_ref()
+            .map(|player| player.buffered_ranges())
+            .unwrap_or_default();
+        if buffered_ranges != self.buffered_ranges {
+            self.buffered_ranges = buffered_ranges.clone();
+            sender
+                .output(PlayerComponentOutput::BufferedRangesChanged(buffered_ranges))
+                .unwrap_or_default();
+        }
+    }
+
     fn set_zoom(
         &mut self,
         new_scale: Option<f64>,
@@ -545,3 +914,31 @@ impl PlayerComponentModel {
         zoom
     }
 }
+
+fn statistics_text(statistics: &Statistics) -> String {
+    let unknown = || fl!("statistics", "unknown");
+    [
+        fl!(
+            "statistics",
+            "decoder",
+            decoder = statistics.video_decoder.clone().unwrap_or_else(unknown)
+        ),
+        fl!(
+            "statistics",
+            "caps",
+            caps = statistics.video_caps.clone().unwrap_or_else(unknown)
+        ),
+        fl!("statistics", "rendered", frames = statistics.rendered_frames),
+        fl!("statistics", "dropped", frames = statistics.dropped_frames),
+        fl!(
+            "statistics",
+            "fps",
+            fps = statistics.fps.map(|fps| format!("{fps:.2}")).unwrap_or_else(unknown)
+        ),
+        statistics
+            .bitrate
+            .map(|bitrate| fl!("statistics", "bitrate", kbits = bitrate / 1000))
+            .unwrap_or_else(|| fl!("statistics", "bitrate-unknown")),
+    ]
+    .join("\n")
+}
@@ -1,4 +1,4 @@
-use anyhow::{Context, Error, Result};
+use anyhow::{Context, Result};
 use chrono::DateTime;
 use gst::TagList;
 use gst_pbutils::{prelude::*, DiscovererInfo, DiscovererResult};
@@ -15,7 +15,12 @@ use std::path::{Path, PathBuf};
 
 use glib::clone;
 
-use crate::localization::helper::fl;
+use crate::{
+    chapter::{chapters_from_toc, Chapter},
+    error::{ErrorDetails, PlayerError},
+    localization::helper::fl,
+    media_info::MediaInfo,
+};
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum DropState {
@@ -28,7 +33,7 @@ pub enum DropState {
 pub struct PlaylistEntryInit {
     pub uri: String,
     pub short_uri: Option<String>,
-    pub error: Option<Error>,
+    pub error: Option<PlayerError>,
 }
 
 pub struct PlaylistEntryModel {
@@ -42,9 +47,12 @@ pub struct PlaylistEntryModel {
     pub info_text: String,
     pub info_tooltip: Option<String>,
     pub date_time: Option<DateTime<chrono::Local>>,
-    pub error: Option<Error>,
+    pub error: Option<PlayerError>,
     pub duration: Option<f64>,
-    pub media_info: Option<DiscovererInfo>,
+    pub chapters: Vec<Chapter>,
+    pub media_info: Option<MediaInfo>,
+    /// Whether the entry matches the filter of the playlist.
+    pub visible: bool,
     pub notify_debouncer: Option<Debouncer<RecommendedWatcher>>,
 }
 
@@ -54,6 +62,7 @@ pub enum PlaylistEntryInput {
     Activate,
     Deactivate,
     FetchMetadata,
+    SetVisible(bool),
     SetDropState(DropState),
     EnterEvent,
     LeaveEvent,
@@ -70,7 +79,7 @@ pub enum PlaylistEntryOutput {
 
 #[derive(Debug)]
 pub enum PlaylistEntryCommandOutput {
-    UpdateMetadata(Result<DiscovererInfo>),
+    UpdateMetadata(Result<DiscovererInfo, PlayerError>),
 }
 
 const NOTIFY_TIMEOUT_SECS: u64 = 2;
@@ -88,6 +97,9 @@ impl FactoryComponent for PlaylistEntryModel {
     view! {
         #[root]
         gtk::ListBoxRow {
+            #[watch]
+            set_visible: self.visible,
+
             gtk::Box {
                 set_hexpand: true,
                 set_orientation: gtk::Orientation::Vertical,
@@ -246,7 +258,9 @@ impl FactoryComponent for PlaylistEntryModel {
             date_time: None,
             error: init.error,
             duration: None,
+            chapters: Vec::new(),
             media_info: None,
+            visible: true,
             notify_debouncer,
         }
     }
@@ -444,6 +458,9 @@ impl FactoryComponent for PlaylistEntryModel {
             PlaylistEntryInput::Deactivate => {
                 self.active = false;
             }
+            PlaylistEntryInput::SetVisible(visible) => {
+                self.visible = visible;
+            }
             PlaylistEntryInput::FetchMetadata => {
                 self.updating = true;
                 let uri = self.uri.clone();
@@ -481,6 +498,7 @@ impl FactoryComponent for PlaylistEntryModel {
             PlaylistEntryCommandOutput::UpdateMetadata(result) => {
                 self.updating = false;
                 self.duration = None;
+                self.chapters.clear();
                 "".clone_into(&mut self.duration_text);
                 self.error = None;
                 self.info_tooltip = None;
@@ -496,6 +514,9 @@ impl FactoryComponent for PlaylistEntryModel {
                                     self.duration_text =
                                         format!("<span font_desc=\"monospace\">{:.0}</span>", duration);
                                 }
+                                if let Some(toc) = info.toc() {
+                                    self.chapters = chapters_from_toc(&toc);
+                                }
                                 if let Some(info) = info.stream_info() {
                                     if let Some(info) = info.downcast_ref::<gst_pbutils::DiscovererContainerInfo>() {
                                         if let Some(tags) = info.tags() {
@@ -525,23 +546,30 @@ impl FactoryComponent for PlaylistEntryModel {
                                     .iter()
                                     .map(|x| x.to_string())
                                     .collect();
-                                self.error = Some(anyhow::anyhow!("{}", details.join(", ")));
+                                self.error = Some(PlayerError::MissingPlugin {
+                                    details: ErrorDetails::new(details.join(", ")),
+                                    installer_details: details,
+                                });
                             }
                             DiscovererResult::UriInvalid => {
-                                self.error = Some(anyhow::anyhow!(fl!("invalid-uri", uri = self.uri.clone())));
+                                self.error = Some(PlayerError::InvalidUri(ErrorDetails::new(fl!(
+                                    "invalid-uri",
+                                    uri = self.uri.clone()
+                                ))));
                             }
                             DiscovererResult::Timeout => {
-                                self.error = Some(anyhow::anyhow!(fl!("file-discovery-timeout")));
+                                self.error =
+                                    Some(PlayerError::Timeout(ErrorDetails::new(fl!("file-discovery-timeout"))));
                             }
                             DiscovererResult::Busy => unreachable!(),
                             DiscovererResult::Error => unreachable!(),
                             _ => (),
                         }
-                        self.media_info = Some(info);
+                        self.media_info = Some(MediaInfo::from(&info));
                     }
                 }
                 if let Some(error) = &self.error {
-                    self.info_text = format!("{error:?}");
+                    self.info_text = error.to_string();
                     self.info_tooltip = Some(self.info_text.clone())
                 }
                 sender
@@ -574,7 +602,7 @@ impl PlaylistEntryModel {
     }
 }
 
-fn get_media_info(uri: &str) -> Result<DiscovererInfo> {
+fn get_media_info(uri: &str) -> Result<DiscovererInfo, PlayerError> {
     let timeout: gst::ClockTime = gst::ClockTime::from_seconds(10);
     let discoverer = gst_pbutils::Discoverer::new(timeout)?;
     let info = discoverer.discover_uri(uri)?;
@@ -0,0 +1,78 @@
+use chrono::{DateTime, NaiveDate};
+
+use super::PlaylistEntryModel;
+use crate::media_info::MediaInfo;
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Filter of the playlist entries, which is parsed from the text of the search entry.
+///
+/// All words have to be contained case-insensitively in the short URI, the tags, the title, the container or the
+/// codecs of an entry. The words `from:YYYY-MM-DD` and `to:YYYY-MM-DD` restrict the start time of an entry.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PlaylistFilter {
+    terms: Vec<String>,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+}
+
+impl PlaylistFilter {
+    pub fn parse(text: &str) -> Self {
+        let mut filter = Self::default();
+        for word in text.split_whitespace() {
+            if let Some(date) = word.strip_prefix("from:").and_then(parse_date) {
+                filter.from = Some(date);
+            } else if let Some(date) = word.strip_prefix("to:").and_then(parse_date) {
+                filter.to = Some(date);
+            } else {
+                filter.terms.push(word.to_lowercase());
+            }
+        }
+        filter
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty() && self.from.is_none() && self.to.is_none()
+    }
+
+    pub fn matches(
+        &self,
+        short_uri: &str,
+        date_time: Option<&DateTime<chrono::Local>>,
+        media_info: Option<&MediaInfo>,
+    ) -> bool {
+        if self.from.is_some() || self.to.is_some() {
+            let Some(date) = date_time.map(|date_time| date_time.date_naive()) else {
+                return false;
+            };
+            if self.from.is_some_and(|from| date < from) || self.to.is_some_and(|to| date > to) {
+                return false;
+            }
+        }
+        if self.terms.is_empty() {
+            return true;
+        }
+
+        let mut values = vec![short_uri.to_lowercase()];
+        if let Some(media_info) = media_info {
+            values.extend(media_info.title.iter().map(|title| title.to_lowercase()));
+            values.extend(media_info.container.iter().map(|container| container.to_lowercase()));
+            values.extend(media_info.tags.values().map(|value| value.to_lowercase()));
+            for stream in &media_info.streams {
+                values.extend(stream.codec.iter().map(|codec| codec.to_lowercase()));
+                values.extend(stream.tags.values().map(|value| value.to_lowercase()));
+            }
+        }
+        self.terms
+            .iter()
+            .all(|term| values.iter().any(|value| value.contains(term.as_str())))
+    }
+
+    pub(super) fn matches_entry(&self, entry: &PlaylistEntryModel) -> bool {
+        self.matches(&entry.short_uri, entry.date_time.as_ref(), entry.media_info.as_ref())
+    }
+}
+
+fn parse_date(text: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(text, DATE_FORMAT).ok()
+}
@@ -1,6 +1,9 @@
 use mxl_relm4_components::relm4::prelude::DynamicIndex;
 use std::path::PathBuf;
 
+use super::model::InsertMode;
+use crate::{error::PlayerError, media_info::MediaInfo};
+
 #[derive(Debug, Clone, Copy)]
 pub enum PlaylistState {
     Stopped,
@@ -12,6 +15,8 @@ pub enum PlaylistState {
 pub enum RepeatMode {
     Off,
     All,
+    /// Loop the current entry.
+    One,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -20,6 +25,14 @@ pub enum SortOrder {
     ShortUri,
 }
 
+/// How the entries of a loaded playlist file are added to the playlist.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoadMode {
+    /// Remove all entries before adding the loaded ones.
+    Replace,
+    Append,
+}
+
 #[derive(Debug, Clone, Copy)]
 pub enum PlaylistChange {
     Added,
@@ -34,33 +47,77 @@ pub enum PlaylistComponentInput {
     Stop,
     Previous,
     Next,
+    /// The current entry was played to its end, continue with respect to the repeat and shuffle mode.
+    EndOfStream,
     PlayerPlaying,
     PlayerStopped,
+    /// The player continued gaplessly with the next URI.
+    GaplessSwitched(String),
     Activate(usize),
     Switch(DynamicIndex),
     EndOfPlaylist(DynamicIndex),
+    /// Add files to the playlist, playlist files are replaced by their entries.
     Add(Vec<PathBuf>),
     AddBefore(DynamicIndex, Vec<PathBuf>),
     AddAfter(DynamicIndex, Vec<PathBuf>),
     Remove(DynamicIndex),
+    /// Remove all given entries.
+    RemoveIndices(Vec<DynamicIndex>),
     Updated(DynamicIndex),
     Move(DynamicIndex, usize),
+    /// Move the given entries as a group before the entry at the position.
+    MoveIndices(Vec<DynamicIndex>, usize),
     FetchMetadata,
+    /// Fetch the metadata of the given entries again.
+    FetchMetadataIndices(Vec<DynamicIndex>),
+    /// Copy the URIs of the given entries line by line to the clipboard.
+    CopyUris(Vec<DynamicIndex>),
+    SelectAll,
+    /// Remove the selected entries.
+    RemoveSelected,
+    /// Fetch the metadata of the selected entries again.
+    FetchMetadataSelected,
+    /// Copy the URIs of the selected entries to the clipboard.
+    CopySelectedUris,
     FileChooserRequest,
     Sort(SortOrder),
     ToggleRepeat,
+    ToggleShuffle,
+    /// Filter the visible entries by the text of the search entry, see [`PlaylistFilter`](super::filter::PlaylistFilter).
+    SetFilter(String),
+    /// Whether the navigation to the next and previous entry skips entries, which do not match the filter.
+    SetFilterNavigation(bool),
+    /// Load the entries of a M3U, XSPF or PLS file depending on the file extension.
+    Load(PathBuf, LoadMode),
+    /// Save the playlist to a M3U, XSPF or PLS file depending on the file extension.
+    Save(PathBuf),
+    /// The selected entries changed.
+    SelectionChanged,
 }
 
 #[derive(Debug)]
 pub enum PlaylistComponentOutput {
     PlaylistChanged(PlaylistChange),
     SwitchUri(String),
+    /// URI to continue with gaplessly, when the current URI is about to finish.
+    NextUri(Option<String>),
+    /// The playlist continued gaplessly with the entry of the URI.
+    GaplessSwitched(String),
     EndOfPlaylist,
     StateChanged(PlaylistState),
     FileChooserRequest,
+    /// The media info of the entry at the index was updated, e.g. to refresh the shown media info of the selected
+    /// entry.
+    MediaInfoUpdated(usize, MediaInfo),
+    /// The selection changed, the media info of the single selected entry is `None` if not exactly one entry with a
+    /// known media info is selected.
+    SelectedMediaInfo(Option<MediaInfo>),
+    Error(PlayerError),
 }
 
 #[derive(Debug)]
 pub enum PlaylistCommandOutput {
     ShowPlaceholder(bool),
+    /// The added directories were replaced by the media files they contain.
+    DirectoriesExpanded(InsertMode, Vec<PathBuf>),
 }
@@ -1,4 +1,5 @@
 mod factory;
+pub mod filter;
 pub mod messages;
 pub mod model;
 mod widget;
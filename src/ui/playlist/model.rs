@@ -1,21 +1,28 @@
 use log::*;
 use mxl_relm4_components::relm4::{
-    adw::prelude::*, factory::FactoryVecDeque, gtk::gdk::DragAction, prelude::*, Sender,
+    adw::prelude::*, factory::FactoryVecDeque, gtk::gdk::DragAction, gtk::glib, prelude::*, Sender,
 };
 use std::path::PathBuf;
 
+use crate::error::{ErrorDetails, PlayerError};
+use crate::media_directory;
+use crate::media_info::MediaInfo;
+use crate::playlist_file::{self, PlaylistFileEntry, PlaylistLocation};
 use crate::ui::playlist::messages::{
     PlaylistChange, PlaylistCommandOutput, PlaylistComponentInput, PlaylistComponentOutput, PlaylistState, RepeatMode,
     SortOrder,
 };
 use crate::uri_helpers::uri_from_pathbuf;
 
-use super::factory::PlaylistEntryInit;
 pub use super::factory::PlaylistEntryModel;
+use super::factory::{PlaylistEntryInit, PlaylistEntryInput};
+use super::filter::PlaylistFilter;
 
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct PlaylistComponentInit {
     pub uris: Vec<PathBuf>,
+    /// Maximum depth of subdirectories, which are searched for media files when adding a directory.
+    pub max_directory_depth: Option<usize>,
 }
 
 pub struct PlaylistComponentModel {
@@ -24,10 +31,21 @@ pub struct PlaylistComponentModel {
     pub state: PlaylistState,
     pub show_placeholder: bool,
     pub repeat: RepeatMode,
+    pub shuffle: bool,
+    pub filter: PlaylistFilter,
+    /// Whether the navigation skips entries, which do not match the filter.
+    pub filter_navigation: bool,
+    /// Permutation of all entries, which is played in shuffle mode.
+    pub(super) shuffle_order: Vec<DynamicIndex>,
+    /// Entries played in shuffle mode, the last one is the current entry.
+    pub(super) shuffle_history: Vec<DynamicIndex>,
+    pub(super) next_uri: Option<String>,
+    pub(super) max_directory_depth: Option<usize>,
 }
 
 #[allow(dead_code)]
-pub(super) enum InsertMode {
+#[derive(Debug)]
+pub enum InsertMode {
     Front,
     AtIndex(DynamicIndex),
     Back,
@@ -85,8 +103,29 @@ impl PlaylistComponentModel {
             };
         }
 
+        if uris.iter().any(|path| path.is_dir()) {
+            // Walking the directories and typefinding their files may take a while, so it must not block the UI:
+            let max_depth = self.max_directory_depth;
+            let paths = uris.clone();
+            sender.spawn_oneshot_command(move || {
+                let paths = paths
+                    .into_iter()
+                    .flat_map(|path| {
+                        if path.is_dir() {
+                            media_directory::expand_directory(&path, max_depth)
+                        } else {
+                            vec![path]
+                        }
+                    })
+                    .collect();
+                PlaylistCommandOutput::DirectoriesExpanded(insert_mode, paths)
+            });
+            return;
+        }
+
+        let uris = self.expand_playlist_files(sender, uris);
         let mut edit = self.uris.guard();
-        for uri in uris {
+        for uri in &uris {
             match uri_from_pathbuf(uri) {
                 Ok(file) => {
                     let file_name = uri.file_name().map(|x| x.to_str().unwrap_or_default().to_string());
@@ -111,7 +150,7 @@ impl PlaylistComponentModel {
                         PlaylistEntryInit {
                             uri: file,
                             short_uri: file_name,
-                            error: Some(error),
+                            error: Some(PlayerError::InvalidUri(ErrorDetails::new(format!("{error:#}")))),
                         }
                     );
                 }
@@ -124,6 +163,46 @@ impl PlaylistComponentModel {
             .output_sender()
             .emit(PlaylistComponentOutput::PlaylistChanged(PlaylistChange::Added));
         drop(edit);
+        self.update_shuffle_order();
+        if !self.filter.is_empty() {
+            self.apply_filter();
+        }
+    }
+
+    /// Replaces playlist files by their entries.
+    fn expand_playlist_files(&self, sender: &ComponentSender<Self>, paths: &[PathBuf]) -> Vec<PathBuf> {
+        let mut expanded = Vec::with_capacity(paths.len());
+        for path in paths {
+            if playlist_file::is_playlist_file(path) {
+                match playlist_file::load(path) {
+                    Ok(entries) => expanded.extend(playlist_file_paths(entries)),
+                    Err(error) => {
+                        error!("{error:#}");
+                        sender
+                            .output_sender()
+                            .emit(PlaylistComponentOutput::Error(error.into()));
+                    }
+                }
+            } else {
+                expanded.push(path.clone());
+            }
+        }
+        expanded
+    }
+
+    /// The entries of the playlist to save them into a playlist file.
+    pub fn playlist_file_entries(&self) -> Vec<PlaylistFileEntry> {
+        self.uris
+            .iter()
+            .map(|entry| PlaylistFileEntry {
+                location: PlaylistLocation::from_uri(&entry.uri),
+                title: entry
+                    .media_info
+                    .as_ref()
+                    .and_then(|media_info| media_info.title.clone()),
+                duration: entry.duration,
+            })
+            .collect()
     }
 
     pub(super) fn sort_factory(&mut self, order: &SortOrder) {
@@ -162,14 +241,36 @@ impl PlaylistComponentModel {
 
     pub(super) fn previous(&mut self, sender: &ComponentSender<Self>) {
         if let Some(index) = self.index.as_ref() {
-            if let Some(i) = index.current_index().checked_sub(1) {
-                if let Some(entry) = self.uris.guard().get(i) {
-                    // Switch to previous file:
-                    debug!("Playlist previous -> switch to index {i}");
-                    sender.input(PlaylistComponentInput::Switch(entry.index.clone()));
+            if self.shuffle && self.shuffle_history.len() > 1 {
+                // Walk back through the history, the current entry is added again when switching to it:
+                self.shuffle_history.pop();
+                if let Some(entry) = self.shuffle_history.pop() {
+                    debug!(
+                        "Playlist previous -> switch to shuffle history index {}",
+                        entry.current_index()
+                    );
+                    sender.input(PlaylistComponentInput::Switch(entry));
                     return;
                 }
             }
+            if self.shuffle {
+                if let Some(entry) = self.uris.guard().get(index.current_index()) {
+                    // Restart playback of the first file in the shuffle history:
+                    debug!("Playlist previous -> switch to index {}", index.current_index());
+                    sender.input(PlaylistComponentInput::Switch(entry.index.clone()));
+                }
+                return;
+            }
+            if let Some(entry) = (0..index.current_index())
+                .rev()
+                .filter_map(|i| self.uris.get(i))
+                .find(|entry| self.is_navigable(entry))
+            {
+                // Switch to previous file:
+                debug!("Playlist previous -> switch to index {}", entry.index.current_index());
+                sender.input(PlaylistComponentInput::Switch(entry.index.clone()));
+                return;
+            }
             if let Some(entry) = self.uris.guard().get(index.current_index()) {
                 // Restart playback of current first file in playlist:
                 debug!("Playlist previous -> switch to index {}", index.current_index());
@@ -178,27 +279,248 @@ impl PlaylistComponentModel {
         }
     }
 
+    /// Skip to the following entry, even if the current entry is repeated.
     pub(super) fn next(&mut self, sender: &ComponentSender<Self>) {
+        let index = self.following_entry();
+        self.switch_or_end(sender, index);
+    }
+
+    /// Continue with the next entry after the current entry was played to its end.
+    pub(super) fn end_of_stream(&mut self, sender: &ComponentSender<Self>) {
+        let index = self.next_entry();
+        self.switch_or_end(sender, index);
+    }
+
+    fn switch_or_end(&self, sender: &ComponentSender<Self>, index: Option<DynamicIndex>) {
         if let Some(current_index) = self.index.as_ref() {
-            if let Some(i) = current_index.current_index().checked_add(1) {
-                if let Some(entry) = self.uris.guard().get(i) {
-                    // Switch to next file:
-                    debug!("Playlist next -> switch to index {i}");
-                    sender.input(PlaylistComponentInput::Switch(entry.index.clone()));
-                    return;
-                }
+            if let Some(index) = index {
+                debug!("Playlist next -> switch to index {}", index.current_index());
+                sender.input(PlaylistComponentInput::Switch(index));
+            } else {
+                sender.input(PlaylistComponentInput::EndOfPlaylist(current_index.clone()));
             }
-            match self.repeat {
-                RepeatMode::Off => (),
-                RepeatMode::All => {
-                    if let Some(entry) = self.uris.guard().get(0) {
-                        debug!("Playlist repeat all -> switch to index 0");
-                        sender.input(PlaylistComponentInput::Switch(entry.index.clone()));
-                        return;
-                    }
+        }
+    }
+
+    /// The entry, which is played after the current entry with respect to the repeat mode.
+    pub(super) fn next_entry(&self) -> Option<DynamicIndex> {
+        match self.repeat {
+            RepeatMode::One => self.index.clone(),
+            RepeatMode::Off | RepeatMode::All => self.following_entry(),
+        }
+    }
+
+    /// The entry, which follows the current entry in the playlist or in the shuffle order.
+    fn following_entry(&self) -> Option<DynamicIndex> {
+        self.following_entry_except(&[])
+    }
+
+    /// The following entry, which skips the excluded entries.
+    fn following_entry_except(&self, excluded: &[DynamicIndex]) -> Option<DynamicIndex> {
+        let current_index = self.index.as_ref()?;
+        let is_navigable = |entry: &PlaylistEntryModel| self.is_navigable(entry) && !excluded.contains(&entry.index);
+        if self.shuffle {
+            let position = self
+                .shuffle_order
+                .iter()
+                .position(|index| index == current_index)
+                .map_or(0, |position| position + 1);
+            let is_navigable_index =
+                |index: &&DynamicIndex| self.uris.get(index.current_index()).is_some_and(is_navigable);
+            if let Some(index) = self.shuffle_order.iter().skip(position).find(is_navigable_index) {
+                return Some(index.clone());
+            }
+            return match self.repeat {
+                RepeatMode::All => self.shuffle_order.iter().find(is_navigable_index).cloned(),
+                RepeatMode::Off | RepeatMode::One => None,
+            };
+        }
+        if let Some(entry) = (current_index.current_index() + 1..self.uris.len())
+            .filter_map(|i| self.uris.get(i))
+            .find(|entry| is_navigable(entry))
+        {
+            return Some(entry.index.clone());
+        }
+        match self.repeat {
+            RepeatMode::All => (0..self.uris.len())
+                .filter_map(|i| self.uris.get(i))
+                .find(|entry| is_navigable(entry))
+                .map(|entry| entry.index.clone()),
+            RepeatMode::Off | RepeatMode::One => None,
+        }
+    }
+
+    /// The media info of the entry, if it is the only selected entry.
+    pub fn selected_media_info(&self) -> Option<MediaInfo> {
+        match self.selected_indices().as_slice() {
+            [index] => self.uris.get(index.current_index())?.media_info.clone(),
+            _ => None,
+        }
+    }
+
+    /// The selected entries in the order of the playlist.
+    pub fn selected_indices(&self) -> Vec<DynamicIndex> {
+        let mut indices: Vec<_> = self
+            .uris
+            .widget()
+            .selected_rows()
+            .iter()
+            .filter_map(|row| usize::try_from(row.index()).ok())
+            .filter_map(|i| self.uris.get(i))
+            .map(|entry| entry.index.clone())
+            .collect();
+        indices.sort_by_key(|index| index.current_index());
+        indices
+    }
+
+    /// Removes the entries, the playback continues with the following entry, if the current entry is removed.
+    pub(super) fn remove_indices(&mut self, sender: &ComponentSender<Self>, indices: &[DynamicIndex]) {
+        if let Some(current_index) = self.index.as_ref() {
+            if indices.contains(current_index) {
+                let index = self.following_entry_except(indices);
+                self.switch_or_end(sender, index);
+                // The switch is only queued, so the removed entry must not determine the next URI:
+                self.index = None;
+            }
+        }
+        let mut positions: Vec<_> = indices.iter().map(|index| index.current_index()).collect();
+        positions.sort_unstable();
+        positions.dedup();
+        let mut edit = self.uris.guard();
+        for position in positions.into_iter().rev() {
+            edit.remove(position);
+        }
+        drop(edit);
+        self.update_shuffle_order();
+        self.update_next_uri(sender);
+        sender
+            .command_sender()
+            .emit(PlaylistCommandOutput::ShowPlaceholder(self.uris.guard().is_empty()));
+        sender
+            .output_sender()
+            .emit(PlaylistComponentOutput::PlaylistChanged(PlaylistChange::Removed));
+    }
+
+    /// Moves the entries as a group in their current order before the entry at the position.
+    ///
+    /// The entries are appended to the end of the playlist, if the position is after the last entry.
+    pub(super) fn move_indices(&mut self, indices: &[DynamicIndex], to: usize) {
+        let mut indices = indices.to_vec();
+        indices.sort_by_key(|index| index.current_index());
+        let mut edit = self.uris.guard();
+        // The group is placed before the first entry, which does not belong to the group:
+        let anchor = (to..edit.len())
+            .filter_map(|i| edit.get(i))
+            .find(|entry| !indices.contains(&entry.index))
+            .map(|entry| entry.index.clone());
+        for index in &indices {
+            let anchor_position = anchor.as_ref().map_or(edit.len(), |anchor| anchor.current_index());
+            let from = index.current_index();
+            let to = if from < anchor_position {
+                anchor_position - 1
+            } else {
+                anchor_position
+            };
+            trace!("Move playlist entry from index {from} to {to}");
+            edit.move_to(from, to);
+        }
+    }
+
+    /// Whether the navigation may switch to the entry with respect to the filter.
+    fn is_navigable(&self, entry: &PlaylistEntryModel) -> bool {
+        !self.filter_navigation || self.filter.matches_entry(entry)
+    }
+
+    /// Shows only the entries, which match the filter.
+    pub(super) fn apply_filter(&mut self) {
+        for i in 0..self.uris.len() {
+            if let Some(entry) = self.uris.get(i) {
+                let visible = self.filter.matches_entry(entry);
+                if entry.visible != visible {
+                    self.uris.send(i, PlaylistEntryInput::SetVisible(visible));
                 }
             }
-            sender.input(PlaylistComponentInput::EndOfPlaylist(current_index.clone()));
         }
     }
+
+    pub(super) fn activate(&mut self, index: DynamicIndex) {
+        self.uris.broadcast(PlaylistEntryInput::Deactivate);
+        self.uris.send(index.current_index(), PlaylistEntryInput::Activate);
+        if self.shuffle && self.shuffle_history.last() != Some(&index) {
+            self.shuffle_history.push(index.clone());
+        }
+        self.index = Some(index);
+    }
+
+    pub(super) fn set_shuffle(&mut self, shuffle: bool) {
+        self.shuffle = shuffle;
+        self.shuffle_order.clear();
+        self.shuffle_history.clear();
+        if shuffle {
+            // Start the permutation with the current entry, so that all other entries are played afterwards:
+            let mut order: Vec<_> = self.uris.iter().map(|entry| entry.index.clone()).collect();
+            shuffle_indices(&mut order);
+            if let Some(current_index) = self.index.clone() {
+                order.retain(|index| *index != current_index);
+                order.insert(0, current_index.clone());
+                self.shuffle_history.push(current_index);
+            }
+            self.shuffle_order = order;
+        }
+    }
+
+    /// Keeps the shuffle order in sync with added and removed entries.
+    ///
+    /// Added entries are placed randomly after the current entry, so that they are played in this round.
+    pub(super) fn update_shuffle_order(&mut self) {
+        if !self.shuffle {
+            return;
+        }
+        let entries: Vec<_> = self.uris.iter().map(|entry| entry.index.clone()).collect();
+        self.shuffle_order.retain(|index| entries.contains(index));
+        self.shuffle_history.retain(|index| entries.contains(index));
+        for index in entries {
+            if !self.shuffle_order.contains(&index) {
+                let start = self
+                    .index
+                    .as_ref()
+                    .and_then(|current_index| self.shuffle_order.iter().position(|index| index == current_index))
+                    .map_or(0, |position| position + 1);
+                let position = glib::random_int_range(start as i32, self.shuffle_order.len() as i32 + 1);
+                self.shuffle_order.insert(position as usize, index);
+            }
+        }
+    }
+
+    /// Provides the URI of the next entry, so that the player can switch to it gaplessly.
+    pub(super) fn update_next_uri(&mut self, sender: &ComponentSender<Self>) {
+        let next_uri = self
+            .next_entry()
+            .and_then(|index| self.uris.get(index.current_index()))
+            .map(|entry| entry.uri.clone());
+        if next_uri != self.next_uri {
+            self.next_uri = next_uri.clone();
+            sender.output_sender().emit(PlaylistComponentOutput::NextUri(next_uri));
+        }
+    }
+}
+
+/// Fisher-Yates shuffle.
+fn shuffle_indices(indices: &mut [DynamicIndex]) {
+    for i in (1..indices.len()).rev() {
+        let j = glib::random_int_range(0, i as i32 + 1) as usize;
+        indices.swap(i, j);
+    }
+}
+
+/// The locations of the playlist file entries to add them to the playlist.
+pub(super) fn playlist_file_paths(entries: Vec<PlaylistFileEntry>) -> Vec<PathBuf> {
+    entries
+        .into_iter()
+        .map(|entry| match entry.location {
+            PlaylistLocation::Path(path) => path,
+            // Remote URIs are added like paths, see `uri_from_pathbuf`:
+            PlaylistLocation::Uri(uri) => PathBuf::from(uri),
+        })
+        .collect()
 }
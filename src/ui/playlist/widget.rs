@@ -5,12 +5,14 @@ use relm4_icons::icon_names;
 use glib::clone;
 
 use crate::localization::helper::fl;
+use crate::playlist_file;
+use crate::ui::playlist::filter::PlaylistFilter;
 use crate::ui::playlist::{
     messages::{
-        PlaylistChange, PlaylistCommandOutput, PlaylistComponentInput, PlaylistComponentOutput, PlaylistState,
-        RepeatMode, SortOrder,
+        LoadMode, PlaylistChange, PlaylistCommandOutput, PlaylistComponentInput, PlaylistComponentOutput,
+        PlaylistState, RepeatMode, SortOrder,
     },
-    model::{InsertMode, PlaylistComponentInit, PlaylistComponentModel},
+    model::{playlist_file_paths, InsertMode, PlaylistComponentInit, PlaylistComponentModel},
 };
 
 use super::factory::{PlaylistEntryInput, PlaylistEntryOutput};
@@ -19,6 +21,12 @@ relm4::new_action_group!(SortActionGroup, "sort_action_group");
 relm4::new_stateless_action!(SortByStartTime, SortActionGroup, "sort_by_start_time");
 relm4::new_stateless_action!(SortByShortUri, SortActionGroup, "sort_by_short_uri");
 
+relm4::new_action_group!(SelectionActionGroup, "selection_action_group");
+relm4::new_stateless_action!(SelectAll, SelectionActionGroup, "select_all");
+relm4::new_stateless_action!(RemoveSelected, SelectionActionGroup, "remove_selected");
+relm4::new_stateless_action!(FetchMetadataSelected, SelectionActionGroup, "fetch_metadata_selected");
+relm4::new_stateless_action!(CopyUrisSelected, SelectionActionGroup, "copy_uris_selected");
+
 #[relm4::component(pub)]
 impl Component for PlaylistComponentModel {
     type Init = PlaylistComponentInit;
@@ -50,15 +58,59 @@ impl Component for PlaylistComponentModel {
                     set_tooltip_text: Some(match model.repeat {
                             RepeatMode::Off => fl!("repeat", "none"),
                             RepeatMode::All => fl!("repeat", "all"),
+                            RepeatMode::One => fl!("repeat", "one"),
                         }.as_ref()),
                     #[watch]
                     set_icon_name: match model.repeat {
                             RepeatMode::Off => icon_names::ARROW_REPEAT_ALL_OFF_FILLED,
                             RepeatMode::All => icon_names::ARROW_REPEAT_ALL_FILLED,
+                            RepeatMode::One => icon_names::ARROW_REPEAT_1_FILLED,
                         },
                     connect_clicked[sender] => move |_| {
                         sender.input(PlaylistComponentInput::ToggleRepeat);
                     }
+                },
+                pack_end = &gtk::Button {
+                    set_has_tooltip: true,
+                    #[watch]
+                    set_tooltip_text: Some(&if model.shuffle {
+                            fl!("shuffle", "on")
+                        } else {
+                            fl!("shuffle", "off")
+                        }),
+                    #[watch]
+                    set_icon_name: if model.shuffle {
+                            icon_names::ARROW_SHUFFLE_FILLED
+                        } else {
+                            icon_names::ARROW_SHUFFLE_OFF_FILLED
+                        },
+                    connect_clicked[sender] => move |_| {
+                        sender.input(PlaylistComponentInput::ToggleShuffle);
+                    }
+                },
+                pack_end = &gtk::MenuButton {
+                    set_label: &fl!("selection"),
+
+                    set_menu_model: Some(&{
+                        let menu_model = gtk::gio::Menu::new();
+                        menu_model.append(
+                            Some(&fl!("selection", "select-all")),
+                            Some(&SelectAll::action_name()),
+                        );
+                        menu_model.append(
+                            Some(&fl!("selection", "remove")),
+                            Some(&RemoveSelected::action_name()),
+                        );
+                        menu_model.append(
+                            Some(&fl!("selection", "fetch-metadata")),
+                            Some(&FetchMetadataSelected::action_name()),
+                        );
+                        menu_model.append(
+                            Some(&fl!("selection", "copy-uris")),
+                            Some(&CopyUrisSelected::action_name()),
+                        );
+                        menu_model
+                    }),
                 },
                  pack_end = &gtk::MenuButton {
                     set_label: &fl!("sort-by"),
@@ -78,6 +130,30 @@ impl Component for PlaylistComponentModel {
                 }
             },
 
+            gtk::Box {
+                set_orientation: gtk::Orientation::Horizontal,
+                set_spacing: 6,
+                set_margin_start: 6,
+                set_margin_end: 6,
+                set_margin_bottom: 6,
+
+                gtk::SearchEntry {
+                    set_hexpand: true,
+                    set_placeholder_text: Some(&fl!("search")),
+                    set_tooltip_text: Some(&fl!("search", "desc")),
+                    connect_search_changed[sender] => move |entry| {
+                        sender.input(PlaylistComponentInput::SetFilter(entry.text().to_string()));
+                    },
+                },
+                gtk::CheckButton {
+                    set_label: Some(&fl!("search", "navigation")),
+                    set_tooltip_text: Some(&fl!("search", "navigation-desc")),
+                    connect_toggled[sender] => move |button| {
+                        sender.input(PlaylistComponentInput::SetFilterNavigation(button.is_active()));
+                    },
+                },
+            },
+
             #[name="drop_box"]
             gtk::Box {
                 set_orientation: gtk::Orientation::Vertical,
@@ -92,9 +168,13 @@ impl Component for PlaylistComponentModel {
                     #[local_ref]
                     file_list_box -> gtk::ListBox {
                         add_css_class: "boxed-list",
+                        set_selection_mode: gtk::SelectionMode::Multiple,
                         set_activate_on_single_click: false,
                         connect_row_activated[sender] => move |_, row| {
                             sender.input(PlaylistComponentInput::Activate(row.index() as usize))
+                        },
+                        connect_selected_rows_changed[sender] => move |_| {
+                            sender.input(PlaylistComponentInput::SelectionChanged)
                         }
                     }
                 },
@@ -131,6 +211,37 @@ impl Component for PlaylistComponentModel {
         });
         group.register_for_widget(&root);
 
+        let mut group = RelmActionGroup::<SelectionActionGroup>::new();
+        group.add_action(RelmAction::<SelectAll>::new_stateless(clone!(
+            #[strong]
+            sender,
+            move |_| {
+                sender.input(PlaylistComponentInput::SelectAll);
+            }
+        )));
+        group.add_action(RelmAction::<RemoveSelected>::new_stateless(clone!(
+            #[strong]
+            sender,
+            move |_| {
+                sender.input(PlaylistComponentInput::RemoveSelected);
+            }
+        )));
+        group.add_action(RelmAction::<FetchMetadataSelected>::new_stateless(clone!(
+            #[strong]
+            sender,
+            move |_| {
+                sender.input(PlaylistComponentInput::FetchMetadataSelected);
+            }
+        )));
+        group.add_action(RelmAction::<CopyUrisSelected>::new_stateless(clone!(
+            #[strong]
+            sender,
+            move |_| {
+                sender.input(PlaylistComponentInput::CopySelectedUris);
+            }
+        )));
+        group.register_for_widget(&root);
+
         let uris =
             FactoryVecDeque::builder()
                 .launch(gtk::ListBox::default())
@@ -148,6 +259,13 @@ impl Component for PlaylistComponentModel {
             state: PlaylistState::Stopped,
             show_placeholder: init.uris.is_empty(),
             repeat: RepeatMode::Off,
+            shuffle: false,
+            filter: PlaylistFilter::default(),
+            filter_navigation: false,
+            shuffle_order: Vec::new(),
+            shuffle_history: Vec::new(),
+            next_uri: None,
+            max_directory_depth: init.max_directory_depth,
         };
 
         model.add_uris(&sender, InsertMode::Back, &init.uris);
@@ -158,10 +276,25 @@ impl Component for PlaylistComponentModel {
             .drop_box
             .add_controller(PlaylistComponentModel::new_drop_target(sender.input_sender().clone()));
 
+        // Add controller to copy the URIs of the selected entries:
+        let event_manager = gtk::EventControllerKey::new();
+        event_manager.connect_key_pressed(clone!(
+            #[strong]
+            sender,
+            move |_widget, key, _keycode, modifier| {
+                if modifier.contains(gtk::gdk::ModifierType::CONTROL_MASK) && key == gtk::gdk::Key::c {
+                    sender.input(PlaylistComponentInput::CopySelectedUris);
+                    return glib::Propagation::Stop;
+                }
+                glib::Propagation::Proceed
+            }
+        ));
+        file_list_box.add_controller(event_manager);
+
         ComponentParts { model, widgets }
     }
 
-    fn update(&mut self, msg: Self::Input, sender: ComponentSender<Self>, _: &Self::Root) {
+    fn update(&mut self, msg: Self::Input, sender: ComponentSender<Self>, root: &Self::Root) {
         match msg {
             PlaylistComponentInput::Start => {
                 debug!("Playlist start");
@@ -179,6 +312,7 @@ impl Component for PlaylistComponentModel {
                 PlaylistState::Stopping => {
                     self.uris.broadcast(PlaylistEntryInput::Deactivate);
                     self.index = None;
+                    self.update_next_uri(&sender);
                     sender
                         .output(PlaylistComponentOutput::StateChanged(PlaylistState::Stopped))
                         .unwrap_or_default();
@@ -195,33 +329,57 @@ impl Component for PlaylistComponentModel {
             PlaylistComponentInput::Next => {
                 self.next(&sender);
             }
+            PlaylistComponentInput::EndOfStream => {
+                self.end_of_stream(&sender);
+            }
             PlaylistComponentInput::Activate(index) => {
                 if let Some(entry) = self.uris.get(index) {
                     sender.input(PlaylistComponentInput::Switch(entry.index.clone()))
                 }
             }
             PlaylistComponentInput::Switch(index) => {
-                self.uris.broadcast(PlaylistEntryInput::Deactivate);
-                self.uris.send(index.current_index(), PlaylistEntryInput::Activate);
-                self.index = Some(index.clone());
+                self.activate(index.clone());
                 if let Some(entry) = self.uris.guard().get_mut(index.current_index()) {
                     sender
                         .output(PlaylistComponentOutput::SwitchUri(entry.uri.clone()))
                         .unwrap_or_default();
                 }
+                self.update_next_uri(&sender);
+            }
+            PlaylistComponentInput::GaplessSwitched(uri) => {
+                let index = self.next_entry().filter(|index| {
+                    self.uris
+                        .get(index.current_index())
+                        .is_some_and(|entry| entry.uri == uri)
+                });
+                if let Some(index) = index {
+                    debug!("Playlist gapless switch to index {}", index.current_index());
+                    self.activate(index);
+                    sender
+                        .output(PlaylistComponentOutput::GaplessSwitched(uri))
+                        .unwrap_or_default();
+                } else {
+                    warn!("Cannot find playlist entry of gaplessly switched uri {uri}");
+                }
+                // The player consumed the next URI, so provide the following one:
+                self.next_uri = None;
+                self.update_next_uri(&sender);
             }
             PlaylistComponentInput::EndOfPlaylist(_index) => {
                 self.uris.broadcast(PlaylistEntryInput::Deactivate);
                 self.index = None;
+                self.update_next_uri(&sender);
                 sender
                     .output(PlaylistComponentOutput::EndOfPlaylist)
                     .unwrap_or_default();
             }
             PlaylistComponentInput::Add(files) => {
                 self.add_uris(&sender, InsertMode::Back, &files);
+                self.update_next_uri(&sender);
             }
             PlaylistComponentInput::AddBefore(index, files) => {
                 self.add_uris(&sender, InsertMode::AtIndex(index), &files);
+                self.update_next_uri(&sender);
             }
             PlaylistComponentInput::AddAfter(index, files) => {
                 let edit = self.uris.guard();
@@ -235,43 +393,99 @@ impl Component for PlaylistComponentModel {
                         self.add_uris(&sender, InsertMode::Back, &files);
                     }
                 }
+                self.update_next_uri(&sender);
             }
             PlaylistComponentInput::Remove(index) => {
                 debug!("Remove item {index:?}");
-                if let Some(current_index) = self.index.clone() {
-                    if index == current_index {
-                        self.next(&sender);
-                    }
+                self.remove_indices(&sender, std::slice::from_ref(&index));
+            }
+            PlaylistComponentInput::RemoveIndices(indices) => {
+                debug!("Remove {} items", indices.len());
+                self.remove_indices(&sender, &indices);
+            }
+            PlaylistComponentInput::RemoveSelected => {
+                let selected = self.selected_indices();
+                if !selected.is_empty() {
+                    sender.input(PlaylistComponentInput::RemoveIndices(selected));
                 }
-                self.uris.guard().remove(index.current_index());
-                sender
-                    .command_sender()
-                    .emit(PlaylistCommandOutput::ShowPlaceholder(self.uris.guard().is_empty()));
-                sender
-                    .output_sender()
-                    .emit(PlaylistComponentOutput::PlaylistChanged(PlaylistChange::Removed));
             }
             PlaylistComponentInput::Updated(index) => {
                 sender
                     .output_sender()
                     .emit(PlaylistComponentOutput::PlaylistChanged(PlaylistChange::Updated));
+                if let Some(entry) = self.uris.get(index.current_index()) {
+                    // The metadata of the entry is fetched after adding it:
+                    let visible = self.filter.matches_entry(entry);
+                    if entry.visible != visible {
+                        self.uris
+                            .send(index.current_index(), PlaylistEntryInput::SetVisible(visible));
+                    }
+                    if let Some(media_info) = &entry.media_info {
+                        sender.output_sender().emit(PlaylistComponentOutput::MediaInfoUpdated(
+                            index.current_index(),
+                            media_info.clone(),
+                        ));
+                    }
+                }
                 trace!("Updated item {}", index.current_index());
             }
             PlaylistComponentInput::Move(from, to) => {
+                let selected = self.selected_indices();
+                if selected.len() > 1 && selected.contains(&from) {
+                    // Dragging one of several selected entries moves all of them:
+                    let to = if to > from.current_index() { to + 1 } else { to };
+                    sender.input(PlaylistComponentInput::MoveIndices(selected, to));
+                    return;
+                }
                 let mut edit = self.uris.guard();
                 if let Some(to) = edit.get(to) {
                     let from = from.current_index();
                     let to = to.index.current_index();
                     trace!("Move playlist entry from index {} to {}", from, to);
                     edit.move_to(from, to);
+                    drop(edit);
+                    self.update_next_uri(&sender);
                     sender
                         .output_sender()
                         .emit(PlaylistComponentOutput::PlaylistChanged(PlaylistChange::Reordered));
                 }
             }
+            PlaylistComponentInput::MoveIndices(indices, to) => {
+                trace!("Move {} playlist entries before index {to}", indices.len());
+                self.move_indices(&indices, to);
+                self.update_next_uri(&sender);
+                sender
+                    .output_sender()
+                    .emit(PlaylistComponentOutput::PlaylistChanged(PlaylistChange::Reordered));
+            }
             PlaylistComponentInput::FetchMetadata => {
                 self.uris.broadcast(PlaylistEntryInput::FetchMetadata);
             }
+            PlaylistComponentInput::FetchMetadataIndices(indices) => {
+                for index in indices {
+                    self.uris.send(index.current_index(), PlaylistEntryInput::FetchMetadata);
+                }
+            }
+            PlaylistComponentInput::FetchMetadataSelected => {
+                sender.input(PlaylistComponentInput::FetchMetadataIndices(self.selected_indices()));
+            }
+            PlaylistComponentInput::CopyUris(indices) => {
+                let uris: Vec<_> = indices
+                    .iter()
+                    .filter_map(|index| self.uris.get(index.current_index()))
+                    .map(|entry| entry.uri.as_str())
+                    .collect();
+                if !uris.is_empty() {
+                    debug!("Copy {} URIs to the clipboard", uris.len());
+                    root.clipboard().set_text(&uris.join("\n"));
+                }
+            }
+            PlaylistComponentInput::CopySelectedUris => {
+                sender.input(PlaylistComponentInput::CopyUris(self.selected_indices()));
+            }
+            PlaylistComponentInput::SelectAll => {
+                self.uris.widget().select_all();
+            }
             PlaylistComponentInput::FileChooserRequest => {
                 sender
                     .output(PlaylistComponentOutput::FileChooserRequest)
@@ -280,6 +494,7 @@ impl Component for PlaylistComponentModel {
             PlaylistComponentInput::Sort(order) => {
                 debug!("Sort playlist by {order:?}");
                 self.sort_factory(&order);
+                self.update_next_uri(&sender);
                 sender
                     .output_sender()
                     .emit(PlaylistComponentOutput::PlaylistChanged(PlaylistChange::Reordered));
@@ -287,18 +502,80 @@ impl Component for PlaylistComponentModel {
             PlaylistComponentInput::ToggleRepeat => {
                 self.repeat = match self.repeat {
                     RepeatMode::Off => RepeatMode::All,
-                    RepeatMode::All => RepeatMode::Off,
+                    RepeatMode::All => RepeatMode::One,
+                    RepeatMode::One => RepeatMode::Off,
                 };
                 debug!("Change repeat to {:?}", self.repeat);
+                self.update_next_uri(&sender);
+            }
+            PlaylistComponentInput::SetFilter(text) => {
+                let filter = PlaylistFilter::parse(&text);
+                if filter != self.filter {
+                    debug!("Filter playlist by {filter:?}");
+                    self.filter = filter;
+                    self.apply_filter();
+                    self.update_next_uri(&sender);
+                }
+            }
+            PlaylistComponentInput::SetFilterNavigation(filter_navigation) => {
+                self.filter_navigation = filter_navigation;
+                self.update_next_uri(&sender);
+            }
+            PlaylistComponentInput::ToggleShuffle => {
+                self.set_shuffle(!self.shuffle);
+                debug!("Change shuffle to {}", self.shuffle);
+                self.update_next_uri(&sender);
+            }
+            PlaylistComponentInput::Load(path, mode) => {
+                debug!("Load playlist from {} ({mode:?})", path.display());
+                match playlist_file::load(&path) {
+                    Ok(entries) => {
+                        if mode == LoadMode::Replace && !self.uris.is_empty() {
+                            let indices: Vec<_> = self.uris.iter().map(|entry| entry.index.clone()).collect();
+                            self.remove_indices(&sender, &indices);
+                        }
+                        self.add_uris(&sender, InsertMode::Back, &playlist_file_paths(entries));
+                        self.update_next_uri(&sender);
+                    }
+                    Err(error) => {
+                        error!("{error:#}");
+                        sender
+                            .output_sender()
+                            .emit(PlaylistComponentOutput::Error(error.into()));
+                    }
+                }
+            }
+            PlaylistComponentInput::Save(path) => {
+                debug!("Save playlist to {}", path.display());
+                if let Err(error) = playlist_file::save(&path, &self.playlist_file_entries()) {
+                    error!("{error:#}");
+                    sender
+                        .output_sender()
+                        .emit(PlaylistComponentOutput::Error(error.into()));
+                }
+            }
+            PlaylistComponentInput::SelectionChanged => {
+                sender
+                    .output_sender()
+                    .emit(PlaylistComponentOutput::SelectedMediaInfo(self.selected_media_info()));
             }
         }
     }
 
-    fn update_cmd(&mut self, msg: Self::CommandOutput, _sender: ComponentSender<Self>, _root: &Self::Root) {
+    fn update_cmd(&mut self, msg: Self::CommandOutput, sender: ComponentSender<Self>, _root: &Self::Root) {
         match msg {
             PlaylistCommandOutput::ShowPlaceholder(val) => {
                 self.show_placeholder = val;
             }
+            PlaylistCommandOutput::DirectoriesExpanded(insert_mode, paths) => {
+                // The entry to insert before may have been removed in the meantime:
+                let insert_mode = match insert_mode {
+                    InsertMode::AtIndex(index) if index.current_index() >= self.uris.len() => InsertMode::Back,
+                    insert_mode => insert_mode,
+                };
+                self.add_uris(&sender, insert_mode, &paths);
+                self.update_next_uri(&sender);
+            }
         }
     }
 }
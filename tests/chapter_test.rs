@@ -0,0 +1,145 @@
+use anyhow::Result;
+use mxl_player_components::{
+    chapter::{self, Chapter, PREVIOUS_CHAPTER_THRESHOLD},
+    gst,
+};
+
+fn toc_chapter(uid: &str, title: Option<&str>, start: f64, stop: Option<f64>) -> gst::TocEntry {
+    let seconds_to_nanoseconds = |seconds: f64| (seconds * 1_000_000_000_f64) as i64;
+    let mut entry = gst::TocEntry::new(gst::TocEntryType::Chapter, uid);
+    {
+        let entry = entry.get_mut().unwrap();
+        entry.set_start_stop_times(seconds_to_nanoseconds(start), stop.map_or(-1, seconds_to_nanoseconds));
+        if let Some(title) = title {
+            let mut tags = gst::TagList::new();
+            tags.get_mut()
+                .unwrap()
+                .add::<gst::tags::Title>(&title, gst::TagMergeMode::Append);
+            entry.set_tags(tags);
+        }
+    }
+    entry
+}
+
+fn chapters() -> Vec<Chapter> {
+    vec![
+        Chapter {
+            uid: "intro".to_string(),
+            title: Some("Intro".to_string()),
+            start: 0.0,
+            stop: Some(10.0),
+        },
+        Chapter {
+            uid: "main".to_string(),
+            title: None,
+            start: 10.0,
+            stop: Some(20.0),
+        },
+        Chapter {
+            uid: "credits".to_string(),
+            title: Some("Credits".to_string()),
+            start: 25.0,
+            stop: None,
+        },
+    ]
+}
+
+#[test]
+fn chapters_from_toc() -> Result<()> {
+    gst::init()?;
+
+    let mut edition = gst::TocEntry::new(gst::TocEntryType::Edition, "edition");
+    {
+        let edition = edition.get_mut().unwrap();
+        edition.append_sub_entry(toc_chapter("main", None, 10.0, Some(20.0)));
+        edition.append_sub_entry(toc_chapter("credits", Some("Credits"), 25.0, None));
+        edition.append_sub_entry(toc_chapter("intro", Some("Intro"), 0.0, Some(10.0)));
+    }
+    let mut toc = gst::Toc::new(gst::TocScope::Global);
+    toc.get_mut().unwrap().append_entry(edition);
+
+    assert_eq!(chapter::chapters_from_toc(&toc), chapters());
+    assert!(chapter::chapters_from_toc(&gst::Toc::new(gst::TocScope::Global)).is_empty());
+    Ok(())
+}
+
+#[test]
+fn chapters_from_nested_toc() -> Result<()> {
+    gst::init()?;
+
+    // The main part is split into two sub-chapters, which replace it:
+    let mut main = toc_chapter("main", Some("Main"), 10.0, Some(25.0));
+    {
+        let main = main.get_mut().unwrap();
+        main.append_sub_entry(toc_chapter("part-2", None, 18.0, Some(25.0)));
+        main.append_sub_entry(toc_chapter("part-1", None, 10.0, Some(18.0)));
+    }
+    let mut edition = gst::TocEntry::new(gst::TocEntryType::Edition, "edition");
+    {
+        let edition = edition.get_mut().unwrap();
+        edition.append_sub_entry(main);
+        edition.append_sub_entry(toc_chapter("intro", Some("Intro"), 0.0, Some(10.0)));
+    }
+    let mut toc = gst::Toc::new(gst::TocScope::Global);
+    toc.get_mut().unwrap().append_entry(edition);
+
+    let chapters = chapter::chapters_from_toc(&toc);
+    let uids: Vec<_> = chapters.iter().map(|chapter| chapter.uid.as_str()).collect();
+    assert_eq!(uids, ["intro", "part-1", "part-2"]);
+    assert_eq!(chapter::next_chapter_start(&chapters, 10.0), Some(18.0));
+    assert_eq!(chapter::chapter_at(&chapters, 20.0), Some(2));
+    Ok(())
+}
+
+#[test]
+fn chapter_at() {
+    let chapters = chapters();
+
+    assert_eq!(chapter::chapter_at(&chapters, 0.0), Some(0));
+    assert_eq!(chapter::chapter_at(&chapters, 9.9), Some(0));
+    assert_eq!(chapter::chapter_at(&chapters, 10.0), Some(1));
+    // Between the end of a chapter and the start of the next one:
+    assert_eq!(chapter::chapter_at(&chapters, 22.0), None);
+    // The last chapter has no known end:
+    assert_eq!(chapter::chapter_at(&chapters, 100.0), Some(2));
+    assert_eq!(chapter::chapter_at(&[], 5.0), None);
+}
+
+#[test]
+fn next_chapter_start() {
+    let chapters = chapters();
+
+    assert_eq!(chapter::next_chapter_start(&chapters, 0.0), Some(10.0));
+    assert_eq!(chapter::next_chapter_start(&chapters, 15.0), Some(25.0));
+    assert_eq!(chapter::next_chapter_start(&chapters, 25.0), None);
+}
+
+#[test]
+fn previous_chapter_start() {
+    let chapters = chapters();
+
+    // Shortly after the start of a chapter the previous chapter is selected:
+    assert_eq!(chapter::previous_chapter_start(&chapters, 11.0), Some(0.0));
+    assert_eq!(
+        chapter::previous_chapter_start(&chapters, 10.0 + PREVIOUS_CHAPTER_THRESHOLD),
+        Some(0.0)
+    );
+    // Afterwards the start of the current chapter is selected:
+    assert_eq!(
+        chapter::previous_chapter_start(&chapters, 10.0 + PREVIOUS_CHAPTER_THRESHOLD + 0.1),
+        Some(10.0)
+    );
+    assert_eq!(chapter::previous_chapter_start(&chapters, 30.0), Some(25.0));
+    // There is no chapter before the first one, so its start is selected:
+    assert_eq!(chapter::previous_chapter_start(&chapters, 2.0), Some(0.0));
+    assert_eq!(chapter::previous_chapter_start(&[], 2.0), None);
+}
+
+#[test]
+fn previous_chapter_start_before_first_chapter() {
+    let chapters = &chapters()[1..];
+
+    assert_eq!(chapter::previous_chapter_start(chapters, 11.0), Some(10.0));
+    // Before the first chapter the start of the media is selected:
+    assert_eq!(chapter::previous_chapter_start(chapters, 5.0), Some(0.0));
+}
@@ -0,0 +1,133 @@
+use anyhow::Result;
+use mxl_player_components::{
+    error::PlayerError,
+    gst::{self, glib},
+    gst_pbutils,
+};
+
+fn classify(error: impl glib::error::ErrorDomain) -> PlayerError {
+    PlayerError::from(glib::Error::new(error, "test error"))
+}
+
+#[test]
+fn classify_core_errors() {
+    use gst::CoreError::*;
+
+    assert!(matches!(classify(MissingPlugin), PlayerError::MissingPlugin { .. }));
+    for error in [
+        Failed,
+        TooLazy,
+        NotImplemented,
+        StateChange,
+        Pad,
+        Thread,
+        Negotiation,
+        Event,
+        Seek,
+        Caps,
+        Tag,
+        Clock,
+        Disabled,
+    ] {
+        assert!(matches!(classify(error), PlayerError::Other(_)), "{error:?}");
+    }
+}
+
+#[test]
+fn classify_resource_errors() {
+    use gst::ResourceError::*;
+
+    for error in [
+        Failed,
+        TooLazy,
+        NotFound,
+        Busy,
+        OpenRead,
+        OpenWrite,
+        OpenReadWrite,
+        Close,
+        Read,
+        Write,
+        Seek,
+        Sync,
+        Settings,
+        NoSpaceLeft,
+        NotAuthorized,
+    ] {
+        assert!(matches!(classify(error), PlayerError::Resource(_)), "{error:?}");
+    }
+}
+
+#[test]
+fn classify_stream_errors() {
+    use gst::StreamError::*;
+
+    assert!(matches!(classify(CodecNotFound), PlayerError::MissingPlugin { .. }));
+    for error in [Decode, Decrypt, DecryptNokey] {
+        assert!(matches!(classify(error), PlayerError::Decoder(_)), "{error:?}");
+    }
+    for error in [NotImplemented, TypeNotFound, WrongType, Demux, Format] {
+        assert!(
+            matches!(classify(error), PlayerError::UnsupportedFormat(_)),
+            "{error:?}"
+        );
+    }
+    for error in [Failed, TooLazy, Encode, Mux] {
+        assert!(matches!(classify(error), PlayerError::Other(_)), "{error:?}");
+    }
+}
+
+#[test]
+fn classify_resource_messages() -> Result<()> {
+    gst::init()?;
+
+    let network_source = gst::ElementFactory::make("tcpclientsrc").build()?;
+    let message = gst::message::Error::builder(gst::ResourceError::OpenRead, "Cannot connect")
+        .src(&network_source)
+        .build();
+    let error = PlayerError::from_message(&message, Vec::new());
+    assert!(matches!(error, Some(PlayerError::Network(_))), "{error:?}");
+
+    let file_source = gst::ElementFactory::make("filesrc").name("source").build()?;
+    let message = gst::message::Warning::builder(gst::ResourceError::NotFound, "Cannot open file")
+        .src(&file_source)
+        .debug("missing.mp4")
+        .build();
+    let error = PlayerError::from_message(&message, Vec::new());
+    assert!(matches!(error, Some(PlayerError::Resource(_))), "{error:?}");
+    let details = error.as_ref().map(PlayerError::details).cloned().unwrap_or_default();
+    assert_eq!(details.message, "Cannot open file");
+    assert_eq!(details.source.as_deref(), Some("/source"));
+    assert_eq!(details.debug.as_deref(), Some("missing.mp4"));
+
+    let message = gst::message::Eos::new();
+    assert!(PlayerError::from_message(&message, Vec::new()).is_none());
+    Ok(())
+}
+
+#[test]
+fn classify_missing_plugin_message() -> Result<()> {
+    gst::init()?;
+    gst_pbutils::pb_utils_init();
+
+    let decoder = gst::ElementFactory::make("decodebin").build()?;
+    let caps = gst::Caps::builder("video/x-unknown-test-codec").build();
+    let missing_plugin = gst_pbutils::missing_decoder_message_new(&decoder, &caps);
+    assert!(gst_pbutils::is_missing_plugin_message(&missing_plugin));
+    let installer_detail = gst_pbutils::missing_plugin_message_get_installer_detail(&missing_plugin)
+        .map(|detail| detail.to_string())
+        .unwrap_or_default();
+    assert!(!installer_detail.is_empty());
+
+    // The decoder reports a generic stream error after the missing plugin message:
+    let message = gst::message::Error::builder(gst::StreamError::Decode, "No decoder available")
+        .src(&decoder)
+        .build();
+    match PlayerError::from_message(&message, vec![installer_detail.clone()]) {
+        Some(PlayerError::MissingPlugin { installer_details, .. }) => {
+            assert_eq!(installer_details, vec![installer_detail])
+        }
+        error => panic!("Expected missing plugin error, got {error:?}"),
+    }
+    Ok(())
+}
@@ -0,0 +1,325 @@
+use anyhow::{Context, Result};
+use mxl_player_components::{
+    error::PlayerError,
+    gst::{self, glib},
+    player::{PlayerBuilder, VideoSink},
+    ui::player::messages::{PlaybackState, PlayerComponentCommand},
+    uri_helpers::uri_from_pathbuf,
+};
+use std::{
+    future::Future,
+    path::PathBuf,
+    sync::mpsc,
+    time::{Duration, Instant},
+};
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+fn test_file() -> Result<PathBuf> {
+    Ok(PathBuf::from(file!())
+        .parent()
+        .expect("Test file has no parent directory")
+        .join("data")
+        .join("Big_Buck_Bunny_720_10s_2MB.mp4")
+        .canonicalize()?)
+}
+
+fn test_uri() -> Result<String> {
+    uri_from_pathbuf(&test_file()?)
+}
+
+/// Drive the main context until the player emitted a command matching the predicate.
+fn wait_for(
+    context: &glib::MainContext,
+    receiver: &mpsc::Receiver<PlayerComponentCommand>,
+    mut predicate: impl FnMut(&PlayerComponentCommand) -> bool,
+) -> Result<PlayerComponentCommand> {
+    let deadline = Instant::now() + TIMEOUT;
+    while Instant::now() < deadline {
+        context.iteration(false);
+        while let Ok(cmd) = receiver.try_recv() {
+            match cmd {
+                PlayerComponentCommand::Error(error) => return Err(error.into()),
+                cmd if predicate(&cmd) => return Ok(cmd),
+                _ => (),
+            }
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    anyhow::bail!("Timed out waiting for the player")
+}
+
+/// Drive the main context until the future of the player finished.
+fn block_on<T: 'static>(context: &glib::MainContext, future: impl Future<Output = Result<T>> + 'static) -> Result<T> {
+    context
+        .block_on(glib::future_with_timeout(TIMEOUT, future))
+        .context("Timed out waiting for the player")?
+}
+
+#[test]
+fn headless_playback() -> Result<()> {
+    gst::init()?;
+
+    let uri = test_uri()?;
+
+    let (sender, receiver) = mpsc::channel::<PlayerComponentCommand>();
+    let player = PlayerBuilder::new().video_sink(VideoSink::Fake).build(sender)?;
+    assert!(player.paintable().is_none());
+
+    player.set_uri(&uri);
+    player.play();
+
+    // The player is not bound to relm4, so drive the main context of the bus watch manually:
+    let context = glib::MainContext::default();
+    let _guard = context.acquire()?;
+    let mut states = vec![];
+    'playback: loop {
+        context.iteration(true);
+        while let Ok(cmd) = receiver.try_recv() {
+            match cmd {
+                PlayerComponentCommand::StateChanged(_, new_state) => states.push(new_state),
+                PlayerComponentCommand::EndOfStream(_) => break 'playback,
+                PlayerComponentCommand::Error(error) => return Err(error.into()),
+                _ => (),
+            }
+        }
+    }
+    assert!(states.contains(&PlaybackState::Playing));
+
+    player.stop();
+
+    Ok(())
+}
+
+#[test]
+fn headless_previous_frame() -> Result<()> {
+    gst::init()?;
+
+    let (sender, receiver) = mpsc::channel::<PlayerComponentCommand>();
+    let player = PlayerBuilder::new()
+        .video_sink(VideoSink::Fake)
+        .seek_accurate(true)
+        .build(sender)?;
+    let context = glib::MainContext::default();
+    let _guard = context.acquire()?;
+
+    player.set_uri(&test_uri()?);
+    player.pause();
+    wait_for(&context, &receiver, |cmd| {
+        matches!(cmd, PlayerComponentCommand::StateChanged(_, PlaybackState::Paused))
+    })?;
+    player.seek(&5.0);
+    wait_for(&context, &receiver, |cmd| {
+        matches!(cmd, PlayerComponentCommand::SeekDone)
+    })?;
+
+    let position = player.position().context("Player has no position")?;
+    let frame_duration = player
+        .frame_duration()
+        .context("Player has no frame duration")?
+        .nseconds() as f64
+        / 1e9;
+    player.previous_frame()?;
+
+    let deadline = Instant::now() + TIMEOUT;
+    let stepped = loop {
+        context.iteration(false);
+        match player.position() {
+            Some(stepped) if stepped < position - frame_duration / 2.0 => break stepped,
+            _ if Instant::now() > deadline => anyhow::bail!("Timed out waiting for the previous frame"),
+            _ => std::thread::sleep(Duration::from_millis(10)),
+        }
+    };
+    assert!(
+        (position - frame_duration - stepped).abs() < 0.005,
+        "Expected position {} but got {stepped}",
+        position - frame_duration
+    );
+
+    player.stop();
+
+    Ok(())
+}
+
+#[test]
+fn headless_previous_frame_in_loop_region() -> Result<()> {
+    gst::init()?;
+
+    let (sender, receiver) = mpsc::channel::<PlayerComponentCommand>();
+    let player = PlayerBuilder::new()
+        .video_sink(VideoSink::Fake)
+        .seek_accurate(true)
+        .build(sender)?;
+    let context = glib::MainContext::default();
+    let _guard = context.acquire()?;
+
+    player.set_uri(&test_uri()?);
+    player.pause();
+    wait_for(&context, &receiver, |cmd| {
+        matches!(cmd, PlayerComponentCommand::StateChanged(_, PlaybackState::Paused))
+    })?;
+    player.seek(&5.0);
+    wait_for(&context, &receiver, |cmd| {
+        matches!(cmd, PlayerComponentCommand::SeekDone)
+    })?;
+    player.set_loop_start(Some(4.0))?;
+    player.set_loop_end(Some(6.0))?;
+
+    let position = player.position().context("Player has no position")?;
+    player.previous_frame()?;
+
+    let deadline = Instant::now() + TIMEOUT;
+    loop {
+        context.iteration(false);
+        match player.position() {
+            Some(stepped) if stepped < position => break,
+            _ if Instant::now() > deadline => anyhow::bail!("Timed out waiting for the previous frame"),
+            _ => std::thread::sleep(Duration::from_millis(10)),
+        }
+    }
+    assert_eq!(player.loop_region(), Some((4.0, 6.0)));
+
+    // The playback still loops instead of continuing to the end of the stream:
+    player.play();
+    let mut last_position = position;
+    wait_for(&context, &receiver, |cmd| match cmd {
+        PlayerComponentCommand::PositionUpdated(position) => {
+            let looped = *position < last_position - 0.5;
+            last_position = *position;
+            looped
+        }
+        _ => false,
+    })?;
+    assert!(
+        last_position >= 4.0,
+        "Expected position in loop region but got {last_position}"
+    );
+
+    player.stop();
+
+    Ok(())
+}
+
+#[test]
+fn headless_classified_error() -> Result<()> {
+    gst::init()?;
+
+    let dir = tempfile::tempdir()?;
+    let missing_uri = uri_from_pathbuf(&dir.path().join("missing.mp4"))?;
+
+    let (sender, receiver) = mpsc::channel::<PlayerComponentCommand>();
+    let player = PlayerBuilder::new().video_sink(VideoSink::Fake).build(sender)?;
+    let context = glib::MainContext::default();
+    let _guard = context.acquire()?;
+
+    player.set_uri(&missing_uri);
+    player.play();
+
+    let deadline = Instant::now() + TIMEOUT;
+    let error = 'playback: loop {
+        context.iteration(false);
+        while let Ok(cmd) = receiver.try_recv() {
+            if let PlayerComponentCommand::Error(error) = cmd {
+                break 'playback error;
+            }
+        }
+        if Instant::now() > deadline {
+            anyhow::bail!("Timed out waiting for the error");
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    };
+    // The error keeps the domain and source of the pipeline error, which the player forwards as its own error:
+    assert!(matches!(error, PlayerError::Resource(_)), "{error:?}");
+    let details = error.details();
+    assert_eq!(details.domain.as_deref(), Some("gst-resource-error-quark"));
+    assert!(details.source.is_some(), "{details:?}");
+
+    player.stop();
+
+    Ok(())
+}
+
+#[test]
+fn headless_async_operations() -> Result<()> {
+    gst::init()?;
+
+    let (sender, _receiver) = mpsc::channel::<PlayerComponentCommand>();
+    let player = PlayerBuilder::new()
+        .video_sink(VideoSink::Fake)
+        .seek_accurate(true)
+        .build(sender)?;
+    let context = glib::MainContext::default();
+
+    // A player, which never started, is already stopped:
+    block_on(&context, player.stop_async())?;
+
+    // The media info is only discovered once the playback is started or paused:
+    let loaded = player.set_uri_async(&test_uri()?);
+    player.pause();
+    block_on(&context, loaded)?;
+    block_on(&context, player.pause_async())?;
+
+    block_on(&context, player.seek_async(&5.0))?;
+    let position = player.position().context("Player has no position")?;
+    assert!((position - 5.0).abs() < 0.1, "Expected position 5.0 but got {position}");
+
+    block_on(&context, player.play_async())?;
+    block_on(&context, player.stop_async())?;
+    // Stopping a stopped player finishes immediately:
+    block_on(&context, player.stop_async())?;
+
+    Ok(())
+}
+
+#[test]
+fn headless_gapless_switch() -> Result<()> {
+    gst::init()?;
+
+    let dir = tempfile::tempdir()?;
+    let first = dir.path().join("first.mp4");
+    let second = dir.path().join("second.mp4");
+    std::fs::copy(test_file()?, &first)?;
+    std::fs::copy(test_file()?, &second)?;
+    let first_uri = uri_from_pathbuf(&first)?;
+    let second_uri = uri_from_pathbuf(&second)?;
+
+    let (sender, receiver) = mpsc::channel::<PlayerComponentCommand>();
+    let player = PlayerBuilder::new().video_sink(VideoSink::Fake).build(sender)?;
+    let context = glib::MainContext::default();
+    let _guard = context.acquire()?;
+
+    player.set_uri(&first_uri);
+    player.set_next_uri(Some(&second_uri));
+    player.play();
+    wait_for(&context, &receiver, |cmd| {
+        matches!(cmd, PlayerComponentCommand::StateChanged(_, PlaybackState::Playing))
+    })?;
+    // Skip to the end of the first file to switch soon:
+    player.seek(&9.0);
+
+    let cmd = wait_for(&context, &receiver, |cmd| {
+        matches!(
+            cmd,
+            PlayerComponentCommand::GaplessSwitched(_) | PlayerComponentCommand::EndOfStream(_)
+        )
+    })?;
+    assert!(
+        matches!(&cmd, PlayerComponentCommand::GaplessSwitched(uri) if *uri == second_uri),
+        "Expected gapless switch to {second_uri}, got {cmd:?}"
+    );
+    // The player itself still reports the first URI, which is replaced by the tracked one:
+    assert_eq!(player.uri(), Some(second_uri.clone()));
+    player.seek(&9.0);
+
+    let cmd = wait_for(&context, &receiver, |cmd| {
+        matches!(cmd, PlayerComponentCommand::EndOfStream(_))
+    })?;
+    assert!(
+        matches!(&cmd, PlayerComponentCommand::EndOfStream(uri) if *uri == second_uri),
+        "Expected end of stream of {second_uri}, got {cmd:?}"
+    );
+
+    player.stop();
+
+    Ok(())
+}
@@ -0,0 +1,66 @@
+use anyhow::{Context, Result};
+use mxl_player_components::{gst, media_directory};
+use std::{cmp::Ordering, fs, path::PathBuf};
+use tempfile::tempdir;
+
+#[test]
+fn natural_order() {
+    assert_eq!(media_directory::natural_cmp("clip9.mp4", "clip10.mp4"), Ordering::Less);
+    assert_eq!(media_directory::natural_cmp("Clip2.mp4", "clip10.mp4"), Ordering::Less);
+    assert_eq!(
+        media_directory::natural_cmp("clip010.mp4", "clip9.mp4"),
+        Ordering::Greater
+    );
+    assert_eq!(media_directory::natural_cmp("2024-1-2", "2024-01-10"), Ordering::Less);
+    assert_eq!(media_directory::natural_cmp("clip", "clip1"), Ordering::Less);
+
+    let mut names = vec!["b10", "a", "B2", "b1"];
+    names.sort_by(|a, b| media_directory::natural_cmp(a, b));
+    assert_eq!(names, vec!["a", "b1", "B2", "b10"]);
+}
+
+#[test]
+fn expand_directory() -> Result<()> {
+    gst::init()?;
+
+    let video = fs::read(
+        PathBuf::from(file!())
+            .parent()
+            .context("Cannot get test directory")?
+            .join("data/Big_Buck_Bunny_720_10s_2MB.mp4"),
+    )?;
+    let dir = tempdir()?;
+    let root = dir.path();
+    fs::create_dir_all(root.join("2024-03-09/deep"))?;
+    fs::create_dir_all(root.join("2024-03-10"))?;
+    fs::create_dir_all(root.join(".hidden"))?;
+    fs::write(root.join("2024-03-10/clip10.mp4"), &video)?;
+    fs::write(root.join("2024-03-10/clip9.MP4"), &video)?;
+    fs::write(root.join("2024-03-10/clip9.xml"), "<clip/>")?;
+    fs::write(root.join("2024-03-10/playlist.m3u"), "clip9.MP4\n")?;
+    // Detected by typefind:
+    fs::write(root.join("2024-03-09/recording.bin"), &video)?;
+    fs::write(root.join("2024-03-09/notes.txt"), "no media")?;
+    fs::write(root.join("2024-03-09/deep/clip1.mp4"), &video)?;
+    fs::write(root.join(".hidden/clip.mp4"), &video)?;
+
+    assert_eq!(
+        media_directory::expand_directory(root, None),
+        vec![
+            root.join("2024-03-09/deep/clip1.mp4"),
+            root.join("2024-03-09/recording.bin"),
+            root.join("2024-03-10/clip9.MP4"),
+            root.join("2024-03-10/clip10.mp4"),
+        ]
+    );
+    assert_eq!(
+        media_directory::expand_directory(root, Some(1)),
+        vec![
+            root.join("2024-03-09/recording.bin"),
+            root.join("2024-03-10/clip9.MP4"),
+            root.join("2024-03-10/clip10.mp4"),
+        ]
+    );
+    assert!(media_directory::expand_directory(root, Some(0)).is_empty());
+    Ok(())
+}
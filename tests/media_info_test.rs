@@ -0,0 +1,41 @@
+use anyhow::{Context, Result};
+use mxl_player_components::{
+    gst, gst_pbutils,
+    media_info::{MediaInfo, StreamType},
+    uri_helpers::uri_from_pathbuf,
+};
+use std::path::PathBuf;
+
+#[test]
+fn media_info_from_discoverer() -> Result<()> {
+    gst::init()?;
+
+    let path = PathBuf::from(file!())
+        .parent()
+        .context("Cannot get test directory")?
+        .join("data/Big_Buck_Bunny_720_10s_2MB.mp4")
+        .canonicalize()?;
+    let uri = uri_from_pathbuf(&path)?;
+
+    let discoverer = gst_pbutils::Discoverer::new(gst::ClockTime::from_seconds(10))?;
+    let info = MediaInfo::from(&discoverer.discover_uri(&uri)?);
+
+    assert_eq!(info.uri, uri);
+    assert!(info.seekable);
+    assert!(!info.live);
+    assert!(info.duration.is_some_and(|duration| duration > 9_f64));
+
+    let video = info
+        .streams
+        .iter()
+        .find(|stream| stream.stream_type == StreamType::Video)
+        .context("No video stream")?;
+    assert_eq!(video.width, Some(1280));
+    assert_eq!(video.height, Some(720));
+    assert!(video.framerate.is_some());
+    assert_eq!(video.index, 0);
+
+    assert_eq!(MediaInfo::from_json(&info.to_json()?)?, info);
+
+    Ok(())
+}
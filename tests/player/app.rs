@@ -2,10 +2,20 @@ use super::about;
 use log::*;
 use mxl_player_components::{
     actions::{self, Accelerators},
-    gst_play::PlayMediaInfo,
+    chapter::Chapter,
+    error::PlayerError,
+    media_info::MediaInfo,
+    player,
     ui::{
+        media_info::{
+            messages::MediaInfoComponentInput,
+            model::{MediaInfoComponentInit, MediaInfoComponentModel},
+        },
         player::{
-            messages::{PlaybackState, PlayerComponentInput, PlayerComponentOutput},
+            messages::{
+                AudioLevel, PlaybackState, PlayerComponentInput, PlayerComponentOutput, Statistics, SubtitleStream,
+                VideoStream,
+            },
             model::{PlayerComponentInit, PlayerComponentModel},
         },
         playlist::{
@@ -54,6 +64,7 @@ pub struct App {
     reload_player_on_stopped: bool,
     playlist_component: Controller<PlaylistComponentModel>,
     player_component: Controller<PlayerComponentModel>,
+    media_info_component: Controller<MediaInfoComponentModel>,
     update_actions: Vec<Box<dyn Fn(AppState)>>,
 }
 
@@ -67,6 +78,9 @@ pub enum AppMsg {
     Stopped,
     Seek(f64),
     NextFrame,
+    PreviousFrame,
+    NextChapter,
+    PreviousChapter,
     IncreaseVolume,
     DecreaseVolume,
     ResetVolume,
@@ -74,13 +88,15 @@ pub enum AppMsg {
     ChangeVolume(f64),
     IncreaseSpeed,
     DecreaseSpeed,
+    /// Change the direction of the playback at the current speed.
+    ReversePlayback,
     ResetSpeed,
     SetSpeed(f64),
     ChangeSpeed(f64),
     SwitchUri(String),
     Previous,
     Next,
-    PlayerMediaInfoUpdated(PlayMediaInfo),
+    ShowMediaInfo(MediaInfo),
     Quit,
     PlaybackError(anyhow::Error),
     DoAutoStart,
@@ -89,28 +105,50 @@ pub enum AppMsg {
 #[derive(Debug)]
 pub enum AppCmd {
     PlayerInitialized(Option<anyhow::Error>),
-    PlayerMediaInfoUpdated(PlayMediaInfo),
+    PlayerMediaInfoUpdated(MediaInfo),
+    PlayerVideoStreamsChanged(Vec<VideoStream>),
+    PlayerSubtitleStreamsChanged(Vec<SubtitleStream>),
     PlayerDurationChanged(f64),
     PlayerPositionUpdated(f64),
     PlayerSeekDone,
+    PlayerBuffering(u32),
+    PlayerBufferedRangesChanged(Vec<(f64, f64)>),
+    PlayerLoopRegionChanged(Option<(f64, f64)>),
+    PlayerChaptersChanged(Vec<Chapter>),
+    PlayerChapterChanged(Option<usize>),
+    PlayerGaplessSwitched(String),
     PlayerEndOfStream(String),
     PlayerStateChanged(Option<PlaybackState>, PlaybackState),
     PlayerVolumeChanged(f64),
     PlayerSpeedChanged(f64),
+    PlayerPreservePitchChanged(bool),
+    PlayerReversePlaybackSupported(bool),
     PlayerAudioVideoOffsetChanged(i64),
     PlayerSubtitleVideoOffsetChanged(i64),
-    PlayerWarning(anyhow::Error),
-    PlayerError(anyhow::Error),
+    PlayerAudioLevel(AudioLevel),
+    PlayerStatistics(Statistics),
+    PlayerSnapshotSaved(PathBuf),
+    PlayerSnapshotFailed(PlayerError),
+    PlayerWarning(PlayerError),
+    PlayerError(PlayerError),
     PlaylistChanged(PlaylistChange),
     PlaylistSwitchUri(String),
+    PlaylistNextUri(Option<String>),
+    PlaylistGaplessSwitched(String),
     PlaylistEndOfPlaylist,
     PlaylistStateChanged(PlaylistState),
     PlaylistFileChooserRequest,
+    PlaylistMediaInfoUpdated(usize, MediaInfo),
+    PlaylistSelectedMediaInfo(Option<MediaInfo>),
+    PlaylistError(PlayerError),
 }
 
 relm4::new_action_group!(WindowActionGroup, "win");
 relm4::new_stateless_action!(TogglePlayPause, WindowActionGroup, "toggle-play-pause");
 relm4::new_stateless_action!(NextFrame, WindowActionGroup, "next-frame");
+relm4::new_stateless_action!(PreviousFrame, WindowActionGroup, "previous-frame");
+relm4::new_stateless_action!(NextChapter, WindowActionGroup, "next-chapter");
+relm4::new_stateless_action!(PreviousChapter, WindowActionGroup, "previous-chapter");
 relm4::new_stateless_action!(Stop, WindowActionGroup, "stop");
 relm4::new_stateless_action!(NextUri, WindowActionGroup, "next-uri");
 relm4::new_stateless_action!(PrevUri, WindowActionGroup, "prev-uri");
@@ -120,6 +158,7 @@ relm4::new_stateless_action!(ResetVolume, WindowActionGroup, "reset-volume");
 relm4::new_stateless_action!(IncreaseSpeed, WindowActionGroup, "increase-speed");
 relm4::new_stateless_action!(DecreaseSpeed, WindowActionGroup, "decrease-speed");
 relm4::new_stateless_action!(ResetSpeed, WindowActionGroup, "reset-speed");
+relm4::new_stateless_action!(ReversePlayback, WindowActionGroup, "reverse-playback");
 relm4::new_stateless_action!(DumpPipeline, WindowActionGroup, "dump-pipeline");
 
 const VOLUME_DEFAULT: f64 = 1.0;
@@ -130,7 +169,6 @@ const VOLUME_DECREASE: f64 = -VOLUME_INCREASE;
 
 const SPEED_DEFAULT: f64 = 1.0;
 const SPEED_MIN: f64 = 0.2;
-const SPEED_MAX: f64 = 10.0;
 const SPEED_INCREASE: f64 = 0.2;
 const SPEED_DECREASE: f64 = -SPEED_INCREASE;
 
@@ -158,6 +196,8 @@ impl Component for App {
                 model.playlist_component.widget(),
 
                 model.player_component.widget(),
+
+                model.media_info_component.widget(),
             },
         }
     }
@@ -167,42 +207,64 @@ impl Component for App {
         let app = relm4::main_adw_application();
 
         let playlist_component = PlaylistComponentModel::builder()
-            .launch(PlaylistComponentInit { uris: app_init.uris })
+            .launch(PlaylistComponentInit {
+                uris: app_init.uris,
+                ..Default::default()
+            })
             .forward(sender.command_sender(), |msg| match msg {
                 PlaylistComponentOutput::PlaylistChanged(x) => AppCmd::PlaylistChanged(x),
                 PlaylistComponentOutput::SwitchUri(x) => AppCmd::PlaylistSwitchUri(x),
+                PlaylistComponentOutput::NextUri(x) => AppCmd::PlaylistNextUri(x),
+                PlaylistComponentOutput::GaplessSwitched(x) => AppCmd::PlaylistGaplessSwitched(x),
                 PlaylistComponentOutput::EndOfPlaylist => AppCmd::PlaylistEndOfPlaylist,
                 PlaylistComponentOutput::StateChanged(state) => AppCmd::PlaylistStateChanged(state),
                 PlaylistComponentOutput::FileChooserRequest => AppCmd::PlaylistFileChooserRequest,
+                PlaylistComponentOutput::MediaInfoUpdated(x, y) => AppCmd::PlaylistMediaInfoUpdated(x, y),
+                PlaylistComponentOutput::SelectedMediaInfo(x) => AppCmd::PlaylistSelectedMediaInfo(x),
+                PlaylistComponentOutput::Error(x) => AppCmd::PlaylistError(x),
             });
 
         let player_component = {
             PlayerComponentModel::builder()
                 .launch(PlayerComponentInit {
-                    show_seeking_overlay: false,
-                    seek_accurate: false,
-                    compositor: None,
-                    draw_callback: Box::new(|_, _| {}),
-                    drag_gesture: None,
-                    motion_tracker: None,
+                    preserve_pitch: true,
+                    ..Default::default()
                 })
                 .forward(sender.command_sender(), |msg| match msg {
                     PlayerComponentOutput::PlayerInitialized(x) => AppCmd::PlayerInitialized(x),
                     PlayerComponentOutput::MediaInfoUpdated(x) => AppCmd::PlayerMediaInfoUpdated(x),
+                    PlayerComponentOutput::VideoStreamsChanged(x) => AppCmd::PlayerVideoStreamsChanged(x),
+                    PlayerComponentOutput::SubtitleStreamsChanged(x) => AppCmd::PlayerSubtitleStreamsChanged(x),
                     PlayerComponentOutput::DurationChanged(x) => AppCmd::PlayerDurationChanged(x),
                     PlayerComponentOutput::PositionUpdated(x) => AppCmd::PlayerPositionUpdated(x),
                     PlayerComponentOutput::SeekDone => AppCmd::PlayerSeekDone,
+                    PlayerComponentOutput::Buffering(x) => AppCmd::PlayerBuffering(x),
+                    PlayerComponentOutput::BufferedRangesChanged(x) => AppCmd::PlayerBufferedRangesChanged(x),
+                    PlayerComponentOutput::LoopRegionChanged(x) => AppCmd::PlayerLoopRegionChanged(x),
+                    PlayerComponentOutput::ChaptersChanged(x) => AppCmd::PlayerChaptersChanged(x),
+                    PlayerComponentOutput::ChapterChanged(x) => AppCmd::PlayerChapterChanged(x),
+                    PlayerComponentOutput::GaplessSwitched(x) => AppCmd::PlayerGaplessSwitched(x),
                     PlayerComponentOutput::EndOfStream(x) => AppCmd::PlayerEndOfStream(x),
                     PlayerComponentOutput::StateChanged(x, y) => AppCmd::PlayerStateChanged(x, y),
                     PlayerComponentOutput::VolumeChanged(x) => AppCmd::PlayerVolumeChanged(x),
                     PlayerComponentOutput::SpeedChanged(x) => AppCmd::PlayerSpeedChanged(x),
+                    PlayerComponentOutput::PreservePitchChanged(x) => AppCmd::PlayerPreservePitchChanged(x),
+                    PlayerComponentOutput::ReversePlaybackSupported(x) => AppCmd::PlayerReversePlaybackSupported(x),
                     PlayerComponentOutput::AudioVideoOffsetChanged(x) => AppCmd::PlayerAudioVideoOffsetChanged(x),
                     PlayerComponentOutput::SubtitleVideoOffsetChanged(x) => AppCmd::PlayerSubtitleVideoOffsetChanged(x),
+                    PlayerComponentOutput::AudioLevel(x) => AppCmd::PlayerAudioLevel(x),
+                    PlayerComponentOutput::Statistics(x) => AppCmd::PlayerStatistics(x),
+                    PlayerComponentOutput::SnapshotSaved(x) => AppCmd::PlayerSnapshotSaved(x),
+                    PlayerComponentOutput::SnapshotFailed(x) => AppCmd::PlayerSnapshotFailed(x),
                     PlayerComponentOutput::Warning(x) => AppCmd::PlayerWarning(x),
                     PlayerComponentOutput::Error(x) => AppCmd::PlayerError(x),
                 })
         };
 
+        let media_info_component = MediaInfoComponentModel::builder()
+            .launch(MediaInfoComponentInit::default())
+            .detach();
+
         let mut model = App {
             error_channel: app_init.error_channel,
             request_exit: app_init.quit_on_stopped,
@@ -216,12 +278,16 @@ impl Component for App {
             reload_player_on_stopped: false,
             playlist_component,
             player_component,
+            media_info_component,
             update_actions: Vec::new(),
         };
 
         {
             app.set_accelerators_for_action::<TogglePlayPause>(&actions::accelerators(Accelerators::TogglePlayPause));
             app.set_accelerators_for_action::<NextFrame>(&actions::accelerators(Accelerators::NextFrame));
+            app.set_accelerators_for_action::<PreviousFrame>(&actions::accelerators(Accelerators::PreviousFrame));
+            app.set_accelerators_for_action::<NextChapter>(&actions::accelerators(Accelerators::NextChapter));
+            app.set_accelerators_for_action::<PreviousChapter>(&actions::accelerators(Accelerators::PreviousChapter));
             app.set_accelerators_for_action::<NextUri>(&actions::accelerators(Accelerators::Next));
             app.set_accelerators_for_action::<PrevUri>(&actions::accelerators(Accelerators::Previous));
             app.set_accelerators_for_action::<IncreaseVolume>(&actions::accelerators(Accelerators::IncreaseVolume));
@@ -229,6 +295,7 @@ impl Component for App {
             app.set_accelerators_for_action::<IncreaseSpeed>(&actions::accelerators(Accelerators::IncreaseSpeed));
             app.set_accelerators_for_action::<DecreaseSpeed>(&actions::accelerators(Accelerators::DecreaseSpeed));
             app.set_accelerators_for_action::<ResetSpeed>(&actions::accelerators(Accelerators::ResetSpeed));
+            app.set_accelerators_for_action::<ReversePlayback>(&actions::accelerators(Accelerators::ReversePlayback));
             app.set_accelerators_for_action::<DumpPipeline>(&actions::accelerators(Accelerators::DumpPipeline));
         }
 
@@ -267,6 +334,51 @@ impl Component for App {
                 )));
                 action_group.add_action(action);
             }
+            {
+                let action = RelmAction::<NextChapter>::new_stateless(clone!(
+                    #[strong]
+                    sender,
+                    move |_| sender.input(AppMsg::NextChapter)
+                ));
+                model.update_actions.push(Box::new(clone!(
+                    #[strong(rename_to = gio_action)]
+                    action.gio_action(),
+                    move |app_state| {
+                        gio_action.set_enabled(app_state == AppState::Paused || app_state == AppState::Playing);
+                    }
+                )));
+                action_group.add_action(action);
+            }
+            {
+                let action = RelmAction::<PreviousChapter>::new_stateless(clone!(
+                    #[strong]
+                    sender,
+                    move |_| sender.input(AppMsg::PreviousChapter)
+                ));
+                model.update_actions.push(Box::new(clone!(
+                    #[strong(rename_to = gio_action)]
+                    action.gio_action(),
+                    move |app_state| {
+                        gio_action.set_enabled(app_state == AppState::Paused || app_state == AppState::Playing);
+                    }
+                )));
+                action_group.add_action(action);
+            }
+            {
+                let action = RelmAction::<PreviousFrame>::new_stateless(clone!(
+                    #[strong]
+                    sender,
+                    move |_| sender.input(AppMsg::PreviousFrame)
+                ));
+                model.update_actions.push(Box::new(clone!(
+                    #[strong(rename_to = gio_action)]
+                    action.gio_action(),
+                    move |app_state| {
+                        gio_action.set_enabled(app_state == AppState::Paused || app_state == AppState::Playing);
+                    }
+                )));
+                action_group.add_action(action);
+            }
             {
                 let action = RelmAction::<Stop>::new_stateless(clone!(
                     #[strong]
@@ -364,6 +476,21 @@ impl Component for App {
                 )));
                 action_group.add_action(action);
             }
+            {
+                let action = RelmAction::<ReversePlayback>::new_stateless(clone!(
+                    #[strong]
+                    sender,
+                    move |_| sender.input(AppMsg::ReversePlayback)
+                ));
+                model.update_actions.push(Box::new(clone!(
+                    #[strong(rename_to = gio_action)]
+                    action.gio_action(),
+                    move |app_state| {
+                        gio_action.set_enabled(app_state != AppState::Stopped);
+                    }
+                )));
+                action_group.add_action(action);
+            }
             {
                 let action = RelmAction::<ResetSpeed>::new_stateless(clone!(
                     #[strong]
@@ -467,6 +594,29 @@ impl Component for App {
                         .unwrap_or_default();
                 }
             }
+            AppMsg::PreviousFrame => {
+                // Stepping to the previous frame is only allowed while in pause:
+                if self.app_state != AppState::Paused {
+                    sender.input(AppMsg::TogglePlayPause);
+                } else {
+                    self.player_component
+                        .sender()
+                        .send(PlayerComponentInput::PreviousFrame)
+                        .unwrap_or_default();
+                }
+            }
+            AppMsg::NextChapter => {
+                self.player_component
+                    .sender()
+                    .send(PlayerComponentInput::NextChapter)
+                    .unwrap_or_default();
+            }
+            AppMsg::PreviousChapter => {
+                self.player_component
+                    .sender()
+                    .send(PlayerComponentInput::PreviousChapter)
+                    .unwrap_or_default();
+            }
             AppMsg::Stop => {
                 self.playlist_component
                     .sender()
@@ -542,10 +692,13 @@ impl Component for App {
                 }
             }
             AppMsg::IncreaseSpeed => {
-                sender.input(AppMsg::ChangeSpeed(App::clamp_speed(self.speed + SPEED_INCREASE)));
+                sender.input(AppMsg::ChangeSpeed(App::step_speed(self.speed, SPEED_INCREASE)));
             }
             AppMsg::DecreaseSpeed => {
-                sender.input(AppMsg::ChangeSpeed(App::clamp_speed(self.speed + SPEED_DECREASE)));
+                sender.input(AppMsg::ChangeSpeed(App::step_speed(self.speed, SPEED_DECREASE)));
+            }
+            AppMsg::ReversePlayback => {
+                sender.input(AppMsg::ChangeSpeed(-self.speed));
             }
             AppMsg::ResetSpeed => {
                 sender.input(AppMsg::ChangeSpeed(SPEED_DEFAULT));
@@ -563,7 +716,10 @@ impl Component for App {
                         .unwrap_or_default();
                 }
             }
-            AppMsg::PlayerMediaInfoUpdated(_) => (),
+            AppMsg::ShowMediaInfo(media_info) => {
+                self.media_info_component
+                    .emit(MediaInfoComponentInput::SetMediaInfo(Some(media_info)));
+            }
             AppMsg::DumpPipeline => {
                 debug!("Dump pipeline");
                 self.player_component
@@ -618,11 +774,21 @@ impl Component for App {
                 }
             }
             AppCmd::PlayerMediaInfoUpdated(media_info) => {
-                sender.input(AppMsg::PlayerMediaInfoUpdated(media_info));
+                sender.input(AppMsg::ShowMediaInfo(media_info));
+            }
+            AppCmd::PlayerVideoStreamsChanged(streams) => {
+                debug!("player video streams changed: {streams:?}");
+            }
+            AppCmd::PlayerSubtitleStreamsChanged(streams) => {
+                debug!("player subtitle streams changed: {streams:?}");
             }
             AppCmd::PlayerEndOfStream(a) => {
                 debug!("player end of stream : {a}");
-                sender.input(AppMsg::Next)
+                self.app_state = AppState::Next;
+                self.playlist_component
+                    .sender()
+                    .send(PlaylistComponentInput::EndOfStream)
+                    .unwrap_or_default();
             }
             AppCmd::PlayerDurationChanged(duration) => {
                 self.duration = duration;
@@ -634,6 +800,28 @@ impl Component for App {
             AppCmd::PlayerSeekDone => {
                 debug!("player seek done");
             }
+            AppCmd::PlayerBuffering(percent) => {
+                trace!("player buffering {percent}%");
+            }
+            AppCmd::PlayerBufferedRangesChanged(ranges) => {
+                debug!("player buffered ranges changed: {ranges:?}");
+            }
+            AppCmd::PlayerChaptersChanged(chapters) => {
+                debug!("player chapters changed: {chapters:?}");
+            }
+            AppCmd::PlayerChapterChanged(chapter) => {
+                debug!("player chapter changed: {chapter:?}");
+            }
+            AppCmd::PlayerGaplessSwitched(uri) => {
+                debug!("player switched gaplessly to {uri}");
+                self.playlist_component
+                    .sender()
+                    .send(PlaylistComponentInput::GaplessSwitched(uri))
+                    .unwrap_or_default();
+            }
+            AppCmd::PlayerLoopRegionChanged(region) => {
+                debug!("player loop region changed: {region:?}");
+            }
             AppCmd::PlayerStateChanged(old_state, new_state) => {
                 debug!("playback state changed from {old_state:?} to {new_state:?}");
                 match new_state {
@@ -660,16 +848,26 @@ impl Component for App {
             }
             AppCmd::PlayerVolumeChanged(vol) => sender.input(AppMsg::SetVolume(vol)),
             AppCmd::PlayerSpeedChanged(speed) => sender.input(AppMsg::SetSpeed(speed)),
+            AppCmd::PlayerPreservePitchChanged(preserve_pitch) => {
+                debug!("player preserve pitch changed to {preserve_pitch}")
+            }
+            AppCmd::PlayerReversePlaybackSupported(supported) => {
+                debug!("player reverse playback supported: {supported}")
+            }
             AppCmd::PlayerAudioVideoOffsetChanged(offset) => trace!("AppCmd::PlayerAudioVideoOffsetChanged({offset})"),
             AppCmd::PlayerSubtitleVideoOffsetChanged(offset) => {
                 trace!("AppCmd::PlayerSubtitleVideoOffsetChanged({offset})")
             }
+            AppCmd::PlayerAudioLevel(level) => trace!("player audio level: {level:?}"),
+            AppCmd::PlayerStatistics(statistics) => trace!("player statistics: {statistics:?}"),
+            AppCmd::PlayerSnapshotSaved(path) => debug!("player snapshot saved to {path:?}"),
+            AppCmd::PlayerSnapshotFailed(error) => warn!("Cannot save player snapshot: {error:?}"),
             AppCmd::PlayerWarning(error) => {
                 warn!("Internal player warning: {error:?}");
             }
             AppCmd::PlayerError(error) => {
                 error!("Internal player error: {error:?}");
-                sender.input(AppMsg::PlaybackError(error));
+                sender.input(AppMsg::PlaybackError(error.into()));
             }
             AppCmd::PlaylistChanged(change) => {
                 debug!("Playlist changed: {change:?}");
@@ -681,6 +879,15 @@ impl Component for App {
                 }
             }
             AppCmd::PlaylistSwitchUri(uri) => sender.input(AppMsg::SwitchUri(uri)),
+            AppCmd::PlaylistNextUri(uri) => {
+                self.player_component
+                    .sender()
+                    .send(PlayerComponentInput::SetNextUri(uri))
+                    .unwrap_or_default();
+            }
+            AppCmd::PlaylistGaplessSwitched(uri) => {
+                debug!("Playlist switched gaplessly to {uri}");
+            }
             AppCmd::PlaylistEndOfPlaylist => {
                 info!("End of playlist reached");
                 sender.input(AppMsg::Stop);
@@ -698,6 +905,25 @@ impl Component for App {
                 }
             },
             AppCmd::PlaylistFileChooserRequest => (),
+            AppCmd::PlaylistMediaInfoUpdated(index, media_info) => {
+                trace!("playlist entry {index} media info updated:\n{media_info}");
+                // Refresh the media info, if the selected entry was updated:
+                let selected = self.playlist_component.model().selected_indices();
+                if let [selected] = selected.as_slice() {
+                    if selected.current_index() == index {
+                        sender.input(AppMsg::ShowMediaInfo(media_info));
+                    }
+                }
+            }
+            AppCmd::PlaylistSelectedMediaInfo(media_info) => {
+                // Show the media info of the selected entry, even if it was not played yet:
+                if let Some(media_info) = media_info {
+                    sender.input(AppMsg::ShowMediaInfo(media_info));
+                }
+            }
+            AppCmd::PlaylistError(error) => {
+                error!("Playlist error: {error}");
+            }
         }
         self.update_actions();
     }
@@ -708,8 +934,9 @@ impl App {
         volume.clamp(VOLUME_MIN, VOLUME_MAX)
     }
 
-    fn clamp_speed(speed: f64) -> f64 {
-        speed.clamp(SPEED_MIN, SPEED_MAX)
+    /// Steps the absolute speed, the direction is only changed by the reverse playback action.
+    fn step_speed(speed: f64, step: f64) -> f64 {
+        (speed.abs() + step).clamp(SPEED_MIN, player::SPEED_MAX).copysign(speed)
     }
 
     fn update_actions(&self) {
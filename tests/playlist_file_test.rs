@@ -0,0 +1,114 @@
+use anyhow::Result;
+use mxl_player_components::playlist_file::{self, PlaylistFileEntry, PlaylistFormat, PlaylistLocation};
+use std::path::{Path, PathBuf};
+use tempfile::tempdir;
+
+#[test]
+fn parse_m3u() -> Result<()> {
+    let content = "#EXTM3U\n\
+        #EXTINF:10,Big Buck Bunny\n\
+        videos/bunny.mp4\n\
+        \n\
+        # Comment\n\
+        /media/absolute.mkv\n\
+        https://example.com/stream.m3u8\n";
+
+    let entries = playlist_file::parse(PlaylistFormat::M3u, content, Path::new("/reviews"))?;
+
+    assert_eq!(
+        entries,
+        vec![
+            PlaylistFileEntry {
+                location: PathBuf::from("/reviews/videos/bunny.mp4").into(),
+                title: Some("Big Buck Bunny".to_string()),
+                duration: Some(10_f64),
+            },
+            PlaylistFileEntry::new(PathBuf::from("/media/absolute.mkv")),
+            PlaylistFileEntry::new(PlaylistLocation::Uri("https://example.com/stream.m3u8".to_string())),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn parse_pls() -> Result<()> {
+    let content = "[playlist]\n\
+        File2=second.mp4\n\
+        File1=first.mp4\n\
+        Title1=First\n\
+        Length1=-1\n\
+        NumberOfEntries=2\n\
+        Version=2\n";
+
+    let entries = playlist_file::parse(PlaylistFormat::Pls, content, Path::new("/reviews"))?;
+
+    assert_eq!(
+        entries,
+        vec![
+            PlaylistFileEntry {
+                location: PathBuf::from("/reviews/first.mp4").into(),
+                title: Some("First".to_string()),
+                duration: None,
+            },
+            PlaylistFileEntry::new(PathBuf::from("/reviews/second.mp4")),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn parse_xspf() -> Result<()> {
+    let content = r#"<?xml version="1.0" encoding="UTF-8"?>
+        <playlist version="1" xmlns="http://xspf.org/ns/0/">
+          <trackList>
+            <track>
+              <location>file:///media/first%20file.mp4</location>
+              <title>First &amp; only</title>
+              <duration>1500</duration>
+            </track>
+            <track>
+              <location>relative%20file.mp4</location>
+            </track>
+          </trackList>
+        </playlist>"#;
+
+    let entries = playlist_file::parse(PlaylistFormat::Xspf, content, Path::new("/reviews"))?;
+
+    assert_eq!(
+        entries,
+        vec![
+            PlaylistFileEntry {
+                location: PathBuf::from("/media/first file.mp4").into(),
+                title: Some("First & only".to_string()),
+                duration: Some(1.5),
+            },
+            PlaylistFileEntry::new(PathBuf::from("/reviews/relative file.mp4")),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn save_and_load() -> Result<()> {
+    let dir = tempdir()?;
+    let entries = vec![
+        PlaylistFileEntry {
+            location: dir.path().join("videos/first file.mp4").into(),
+            title: Some("First".to_string()),
+            duration: Some(10_f64),
+        },
+        PlaylistFileEntry::new(PathBuf::from("/media/second.mp4")),
+        PlaylistFileEntry::new(PlaylistLocation::Uri("https://example.com/third.mp4".to_string())),
+    ];
+
+    for file_name in ["playlist.m3u", "playlist.m3u8", "playlist.xspf", "playlist.pls"] {
+        let path = dir.path().join(file_name);
+        playlist_file::save(&path, &entries)?;
+        assert_eq!(playlist_file::load(&path)?, entries, "{file_name}");
+    }
+
+    // Locations inside the directory of the playlist are stored relative to it:
+    let content = std::fs::read_to_string(dir.path().join("playlist.m3u"))?;
+    assert!(content.contains("\nvideos/first file.mp4\n"));
+    Ok(())
+}
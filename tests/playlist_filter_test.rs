@@ -0,0 +1,37 @@
+use chrono::{Local, TimeZone};
+use mxl_player_components::{
+    media_info::{MediaInfo, StreamInfo, StreamType},
+    ui::playlist::filter::PlaylistFilter,
+};
+
+#[test]
+fn filter_by_text() {
+    let media_info = MediaInfo {
+        tags: [("artist".to_string(), "Blender Foundation".to_string())].into(),
+        streams: vec![StreamInfo {
+            stream_type: StreamType::Video,
+            codec: Some("H.264 (High Profile)".to_string()),
+            ..Default::default()
+        }],
+        ..Default::default()
+    };
+
+    assert!(PlaylistFilter::parse("").matches("bunny.mp4", None, None));
+    assert!(PlaylistFilter::parse("BUNNY").matches("bunny.mp4", None, None));
+    assert!(!PlaylistFilter::parse("bunny h.264").matches("bunny.mp4", None, None));
+    assert!(PlaylistFilter::parse("bunny h.264").matches("bunny.mp4", None, Some(&media_info)));
+    assert!(PlaylistFilter::parse("blender").matches("bunny.mp4", None, Some(&media_info)));
+    assert!(!PlaylistFilter::parse("vp9").matches("bunny.mp4", None, Some(&media_info)));
+}
+
+#[test]
+fn filter_by_date_range() {
+    let date_time = Local.with_ymd_and_hms(2024, 3, 15, 12, 0, 0).unwrap();
+
+    let filter = PlaylistFilter::parse("from:2024-03-01 to:2024-03-15");
+    assert!(!filter.is_empty());
+    assert!(filter.matches("a.mp4", Some(&date_time), None));
+    assert!(!filter.matches("a.mp4", None, None));
+    assert!(!PlaylistFilter::parse("from:2024-03-16").matches("a.mp4", Some(&date_time), None));
+    assert!(!PlaylistFilter::parse("to:2024-03-14").matches("a.mp4", Some(&date_time), None));
+}